@@ -318,12 +318,11 @@ async fn call_service(
         }
 
         if path == "/~/graphql" {
-            let ctx = std::sync::Arc::new(josh::graphql::context(josh::cache::Transaction::open(
-                &serv.repo_path,
-                None,
-            )?));
+            let transaction = josh::cache::Transaction::open(&serv.repo_path, None)?;
+            let _cancel = josh::cache::CancelOnDrop::new(&transaction);
+            let ctx = std::sync::Arc::new(josh::graphql::context(transaction));
             let root_node = std::sync::Arc::new(josh::graphql::schema());
-            return Ok(josh_proxy::juniper_hyper::graphql(root_node, ctx, req).await?);
+            return Ok(josh_proxy::juniper_hyper::graphql_blocking(root_node, ctx, req).await?);
         }
     }
 
@@ -416,22 +415,26 @@ async fn call_service(
     }
 
     if parsed_url.api == "/~/graphql" {
-        let ctx = std::sync::Arc::new(josh::graphql::context(josh::cache::Transaction::open(
+        let transaction = josh::cache::Transaction::open(
             &serv.repo_path,
             Some(&format!(
                 "refs/josh/upstream/{}/",
                 &josh::to_ns(&parsed_url.upstream_repo),
             )),
-        )?));
+        )?;
+        let _cancel = josh::cache::CancelOnDrop::new(&transaction);
+        let ctx = std::sync::Arc::new(josh::graphql::context(transaction));
         let root_node = std::sync::Arc::new(josh::graphql::repo_schema(
             parsed_url
                 .upstream_repo
                 .strip_suffix(".git")
                 .unwrap_or(&parsed_url.upstream_repo),
         ));
-        return Ok(josh_proxy::juniper_hyper::graphql(root_node, ctx, req)
-            .in_current_span()
-            .await?);
+        return Ok(
+            josh_proxy::juniper_hyper::graphql_blocking(root_node, ctx, req)
+                .in_current_span()
+                .await?,
+        );
     }
 
     if req.uri().query() == Some("info") {
@@ -575,6 +578,11 @@ async fn run_proxy() -> josh::JoshResult<i32> {
 
     josh_proxy::create_repo(&local)?;
     josh::cache::load(&local)?;
+    let pruned = josh::cache::prune_missing(&git2::Repository::open(&local)?)?;
+    tracing::info!(
+        "pruned {} cache entries pointing to missing commits",
+        pruned
+    );
 
     let proxy_service = Arc::new(JoshProxyService {
         port: port,
@@ -32,6 +32,30 @@ where
     })
 }
 
+/// Like `graphql_sync`, but the request is executed on a blocking thread. So the
+/// runtime is not blocked and the returned future can be dropped while the request
+/// is still running, for example when the client disconnects.
+pub async fn graphql_blocking<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error>
+where
+    QueryT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    Ok(match parse_req(req).await {
+        Ok(req) => execute_request_blocking(root_node, context, req).await,
+        Err(resp) => resp,
+    })
+}
+
 pub async fn graphql<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
@@ -179,6 +203,44 @@ where
     resp
 }
 
+async fn execute_request_blocking<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    request: GraphQLBatchRequest<S>,
+) -> Response<Body>
+where
+    QueryT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let res = tokio::task::spawn_blocking(move || {
+        let res = request.execute_sync(&*root_node, &context);
+        (serde_json::to_string_pretty(&res).unwrap(), res.is_ok())
+    })
+    .await;
+    let (body, ok) = match res {
+        Ok(res) => res,
+        Err(_) => return new_response(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let code = if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    let mut resp = new_response(code);
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    *resp.body_mut() = Body::from(body);
+    resp
+}
+
 async fn execute_request<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
@@ -373,8 +435,19 @@ mod tests {
         }
     }
 
-    async fn run_hyper_integration(is_sync: bool) {
-        let port = if is_sync { 3002 } else { 3001 };
+    #[derive(Clone, Copy)]
+    enum Execution {
+        Async,
+        Sync,
+        Blocking,
+    }
+
+    async fn run_hyper_integration(execution: Execution) {
+        let port = match execution {
+            Execution::Async => 3001,
+            Execution::Sync => 3002,
+            Execution::Blocking => 3003,
+        };
         let addr: SocketAddr = ([127, 0, 0, 1], port).into();
 
         let db = Arc::new(Database::new());
@@ -403,10 +476,12 @@ mod tests {
                     };
                     async move {
                         if matches {
-                            if is_sync {
-                                super::graphql_sync(root_node, ctx, req).await
-                            } else {
-                                super::graphql(root_node, ctx, req).await
+                            match execution {
+                                Execution::Async => super::graphql(root_node, ctx, req).await,
+                                Execution::Sync => super::graphql_sync(root_node, ctx, req).await,
+                                Execution::Blocking => {
+                                    super::graphql_blocking(root_node, ctx, req).await
+                                }
                             }
                         } else {
                             let mut resp = Response::new(Body::empty());
@@ -442,11 +517,89 @@ mod tests {
 
     #[tokio::test]
     async fn test_hyper_integration() {
-        run_hyper_integration(false).await
+        run_hyper_integration(Execution::Async).await
     }
 
     #[tokio::test]
     async fn test_sync_hyper_integration() {
-        run_hyper_integration(true).await
+        run_hyper_integration(Execution::Sync).await
+    }
+
+    #[tokio::test]
+    async fn test_blocking_hyper_integration() {
+        run_hyper_integration(Execution::Blocking).await
+    }
+
+    #[tokio::test]
+    async fn test_blocking_cancel() {
+        let root = std::env::temp_dir().join(format!("josh-proxy-test-{}", std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        let path = root.join("repo.git");
+        let repo = git2::Repository::init_bare(&path).unwrap();
+        josh::cache::load(&root).unwrap();
+
+        let commits = {
+            let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+            let mut commits: Vec<git2::Oid> = vec![];
+            for i in 0..5000 {
+                let parent = commits.last().map(|id| repo.find_commit(*id).unwrap());
+                let blob = repo.blob(format!("{}", i).as_bytes()).unwrap();
+                let tree = josh::filter::tree::insert(
+                    &repo,
+                    &josh::filter::tree::empty(&repo),
+                    std::path::Path::new("a/f"),
+                    blob,
+                    0o0100644,
+                )
+                .unwrap();
+                let parents: Vec<_> = parent.iter().collect();
+                commits.push(
+                    repo.commit(None, &signature, &signature, "msg", &tree, &parents)
+                        .unwrap(),
+                );
+            }
+            commits
+        };
+        let head = *commits.last().unwrap();
+
+        let transaction = josh::cache::Transaction::new(repo, None);
+        let cancel = josh::cache::CancelOnDrop::new(&transaction);
+        let ctx = Arc::new(josh::graphql::context(transaction));
+        let root_node = Arc::new(josh::graphql::repo_schema("repo"));
+        let req = hyper::Request::post("/graphql")
+            .header(hyper::header::CONTENT_TYPE, "application/graphql")
+            .body(Body::from(format!(
+                r#"query {{ rev(at: "{}", filter: ":/a") {{ hash }} }}"#,
+                head
+            )))
+            .unwrap();
+
+        // The guard is owned by the request future, like in the josh-proxy handlers
+        let request = {
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                let _cancel = cancel;
+                super::graphql_blocking(root_node, ctx, req).await
+            })
+        };
+
+        // Every filtered commit is new, so all of them end up in the persistent cache
+        // and the progress of the walk is visible to other transactions
+        let filter = josh::filter::parse(":/a").unwrap();
+        let observer = josh::cache::Transaction::open(&path, None).unwrap();
+        let start = std::time::Instant::now();
+        while !observer.known(filter, commits[10]) {
+            assert!(start.elapsed() < Duration::from_secs(60));
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Like a disconnecting client, drop the request future while the walk runs.
+        // The blocking thread then stops and releases the context.
+        request.abort();
+        while Arc::strong_count(&ctx) > 1 {
+            assert!(start.elapsed() < Duration::from_secs(60));
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(!observer.known(filter, head));
     }
 }
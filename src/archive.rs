@@ -0,0 +1,153 @@
+/*
+ * Export a git tree as a tar or zip archive.
+ * Entries are written uncompressed and in tree order, so the same tree
+ * always results in the same archive.
+ */
+
+use super::*;
+use std::io::Write;
+
+struct Entry {
+    path: String,
+    mode: i32,
+    content: Vec<u8>,
+}
+
+fn collect_entries(
+    repo: &git2::Repository,
+    tree: &git2::Tree,
+    root: &str,
+    entries: &mut Vec<Entry>,
+) -> JoshResult<()> {
+    for entry in tree.iter() {
+        let name = entry.name().ok_or(josh_error("no name"))?;
+        let path = format!("{}{}", root, name);
+        match entry.kind() {
+            Some(git2::ObjectType::Tree) => {
+                entries.push(Entry {
+                    path: format!("{}/", path),
+                    mode: entry.filemode(),
+                    content: vec![],
+                });
+                collect_entries(
+                    repo,
+                    &repo.find_tree(entry.id())?,
+                    &format!("{}/", path),
+                    entries,
+                )?;
+            }
+            Some(git2::ObjectType::Blob) => {
+                entries.push(Entry {
+                    path: path,
+                    mode: entry.filemode(),
+                    content: repo.find_blob(entry.id())?.content().to_vec(),
+                });
+            }
+            // Submodules can't be represented in an archive
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn symlink_target(entry: &Entry) -> JoshResult<&str> {
+    std::str::from_utf8(&entry.content).map_err(|_| {
+        josh_error(&format!(
+            "archive: symlink target is not UTF-8 {:?}",
+            entry.path
+        ))
+    })
+}
+
+/// Write the content of `tree` as an uncompressed tar archive.
+/// `mtime` is used as modification time for all entries.
+pub fn tar(repo: &git2::Repository, tree: &git2::Tree, mtime: i64) -> JoshResult<Vec<u8>> {
+    let mut entries = vec![];
+    collect_entries(repo, tree, "", &mut entries)?;
+
+    let mut builder = tar::Builder::new(vec![]);
+    for entry in entries.iter() {
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(std::cmp::max(mtime, 0) as u64);
+        match entry.mode {
+            0o0040000 => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                builder.append_data(&mut header, &entry.path, std::io::empty())?;
+            }
+            0o0120000 => {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_mode(0o777);
+                header.set_size(0);
+                builder.append_link(&mut header, &entry.path, symlink_target(entry)?)?;
+            }
+            mode => {
+                header.set_mode(if mode == 0o0100755 { 0o755 } else { 0o644 });
+                header.set_size(entry.content.len() as u64);
+                builder.append_data(&mut header, &entry.path, &entry.content[..])?;
+            }
+        }
+    }
+    Ok(builder.into_inner()?)
+}
+
+/// The modification time of zip entries, which can only store MS-DOS dates
+/// from 1980 to 2107. Times outside of that range are clamped to it.
+fn dos_time(mtime: i64) -> zip::DateTime {
+    use chrono::{Datelike, Timelike};
+    let t = some_or!(
+        chrono::DateTime::from_timestamp(mtime.clamp(315532800, 4354819198), 0),
+        {
+            return zip::DateTime::default();
+        }
+    )
+    .naive_utc();
+    zip::DateTime::from_date_and_time(
+        t.year() as u16,
+        t.month() as u8,
+        t.day() as u8,
+        t.hour() as u8,
+        t.minute() as u8,
+        t.second() as u8,
+    )
+    .unwrap_or_default()
+}
+
+/// Write the content of `tree` as a zip archive without compression.
+/// `mtime` is used as modification time for all entries.
+pub fn zip(repo: &git2::Repository, tree: &git2::Tree, mtime: i64) -> JoshResult<Vec<u8>> {
+    let mut entries = vec![];
+    collect_entries(repo, tree, "", &mut entries)?;
+
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .last_modified_time(dos_time(mtime));
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    for entry in entries.iter() {
+        match entry.mode {
+            0o0040000 => {
+                writer.add_directory(entry.path.as_str(), options.unix_permissions(0o755))?;
+            }
+            0o0120000 => {
+                writer.add_symlink(
+                    entry.path.as_str(),
+                    symlink_target(entry)?,
+                    options.unix_permissions(0o777),
+                )?;
+            }
+            mode => {
+                let permissions = if mode == 0o0100755 { 0o755 } else { 0o644 };
+                writer.start_file(
+                    entry.path.as_str(),
+                    options
+                        .unix_permissions(permissions)
+                        .large_file(entry.content.len() as u64 >= u32::MAX as u64),
+                )?;
+                writer.write_all(&entry.content)?;
+            }
+        }
+    }
+    Ok(writer.finish()?.into_inner())
+}
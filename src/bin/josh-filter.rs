@@ -1,9 +1,6 @@
 #![deny(warnings)]
 #![warn(unused_extern_crates)]
 
-#[macro_use]
-extern crate rs_tracing;
-
 use std::fs::read_to_string;
 use std::io::Write;
 
@@ -86,7 +83,7 @@ fn run_filter(args: Vec<String>) -> josh::JoshResult<i32> {
         .get_matches_from(args);
 
     if args.is_present("trace") {
-        rs_tracing::open_trace_file!(".").unwrap();
+        josh::open_trace_file(std::path::Path::new("."))?;
     }
 
     if args.is_present("version") {
@@ -131,7 +128,7 @@ fn run_filter(args: Vec<String>) -> josh::JoshResult<i32> {
 
     let finish = defer::defer(|| {
         if args.is_present("trace") {
-            rs_tracing::close_trace_file!();
+            josh::close_trace_file();
         }
         if args.is_present("cache-stats") {
             josh::cache::print_stats();
@@ -192,6 +189,15 @@ fn run_filter(args: Vec<String>) -> josh::JoshResult<i32> {
 
     josh::filter_refs(&transaction, filterobj, &[(src.clone(), t.clone())])?;
 
+    // filter_refs only logs refs it can't filter, so tell why, for example
+    // because paths of :combine collide
+    let src_commit = repo.find_reference(&src)?.peel_to_commit()?;
+    if transaction.get_ref(filterobj, src_commit.id()).is_none() {
+        for warning in josh::filter::compute_warnings(&transaction, filterobj, src_commit.tree()?) {
+            eprintln!("WARNING: {}", warning);
+        }
+    }
+
     let mut all_paths = vec![];
 
     if check_permissions {
@@ -11,8 +11,34 @@ lazy_static! {
         std::sync::Mutex::new(HashMap::new());
     static ref GLOB_MAP: std::sync::Mutex<HashMap<(git2::Oid, git2::Oid), git2::Oid>> =
         std::sync::Mutex::new(HashMap::new());
+    static ref REACHABLE: std::sync::Mutex<HashMap<Vec<git2::Oid>, Reachable>> =
+        std::sync::Mutex::new(HashMap::new());
+    static ref MARKER_COUNTS: std::sync::Mutex<HashMap<MarkerCountKey, usize>> =
+        std::sync::Mutex::new(HashMap::new());
 }
 
+/// Number of entries kept in `MARKER_COUNTS`. The map is cleared when it is full.
+const MARKER_COUNT_LIMIT: usize = 100000;
+
+/// Number of sets kept in `REACHABLE`. Each set holds every commit of a repo,
+/// so only a few are kept. The map is cleared when it is full.
+const REACHABLE_LIMIT: usize = 8;
+
+/// Commits reachable from a set of tips
+pub type Reachable = std::sync::Arc<std::collections::HashSet<git2::Oid>>;
+
+/// Key for the marker count cache: the commit of `refs/josh/meta` the count was
+/// computed from, the commit, the filter, the path and the topic.
+/// Because the meta commit is part of the key, entries become unused when markers
+/// are added.
+pub type MarkerCountKey = (
+    git2::Oid,
+    git2::Oid,
+    filter::Filter,
+    std::path::PathBuf,
+    String,
+);
+
 pub fn load(path: &std::path::Path) -> JoshResult<()> {
     *DB.lock()? = Some(
         sled::Config::default()
@@ -23,6 +49,30 @@ pub fn load(path: &std::path::Path) -> JoshResult<()> {
     Ok(())
 }
 
+/// Remove the entries of the stored commit maps that point to commits no longer
+/// present in `repo`, for example after `git gc`. Returns the number of removed entries.
+pub fn prune_missing(repo: &git2::Repository) -> JoshResult<usize> {
+    let d = DB.lock()?;
+    let db = d.as_ref().ok_or(josh_error("cache not loaded"))?;
+    let odb = repo.odb()?;
+    let mut pruned = 0;
+    for name in db.tree_names() {
+        if name.starts_with(b"_") || name.as_ref() == b"__sled__default" {
+            continue;
+        }
+        let t = db.open_tree(&name)?;
+        for entry in t.iter() {
+            let (from, to) = entry?;
+            let to = git2::Oid::from_bytes(&to)?;
+            if to != git2::Oid::zero() && !odb.exists(to) {
+                t.remove(from)?;
+                pruned += 1;
+            }
+        }
+    }
+    Ok(pruned)
+}
+
 pub fn print_stats() {
     let d = DB.lock().unwrap();
     let db = d.as_ref().unwrap();
@@ -62,6 +112,7 @@ struct Transaction2 {
     missing: Vec<(filter::Filter, git2::Oid)>,
     misses: usize,
     walks: usize,
+    cancel: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
 }
 
 pub struct Transaction {
@@ -115,6 +166,7 @@ impl Transaction {
                 missing: vec![],
                 misses: 0,
                 walks: 0,
+                cancel: None,
             }),
             repo: repo,
             ref_prefix: ref_prefix.unwrap_or("").to_string(),
@@ -122,7 +174,22 @@ impl Transaction {
     }
 
     pub fn clone(&self) -> JoshResult<Transaction> {
-        Transaction::open(self.repo.path(), Some(&self.ref_prefix))
+        let transaction = Transaction::open(self.repo.path(), Some(&self.ref_prefix))?;
+        transaction.t2.borrow_mut().cancel = self.t2.borrow().cancel.clone();
+        Ok(transaction)
+    }
+
+    /// Once `token` is set, history walks in this transaction stop with
+    /// `WalkResult::Cancelled`. Commits filtered before that stay cached.
+    pub fn set_cancel(&self, token: std::sync::Arc<std::sync::atomic::AtomicBool>) {
+        self.t2.borrow_mut().cancel = Some(token);
+    }
+
+    pub fn cancelled(&self) -> bool {
+        if let Some(token) = &self.t2.borrow().cancel {
+            return token.load(std::sync::atomic::Ordering::Relaxed);
+        }
+        return false;
     }
 
     pub fn repo(&self) -> &git2::Repository {
@@ -227,6 +294,44 @@ impl Transaction {
         return GLOB_MAP.lock().unwrap().get(&tree).cloned();
     }
 
+    pub fn insert_marker_count(&self, key: MarkerCountKey, count: usize) {
+        let mut counts = MARKER_COUNTS.lock().unwrap();
+        if counts.len() >= MARKER_COUNT_LIMIT {
+            counts.clear();
+        }
+        counts.insert(key, count);
+    }
+
+    pub fn get_marker_count(&self, key: &MarkerCountKey) -> Option<usize> {
+        return MARKER_COUNTS.lock().unwrap().get(key).cloned();
+    }
+
+    /// All commits reachable from `tips`. The result is kept across transactions
+    /// until one of the tips changes, so later calls with the same tips, like
+    /// requests for the next page of a listing, don't walk the history again.
+    pub fn reachable(&self, tips: &[git2::Oid]) -> JoshResult<Reachable> {
+        let mut tips = tips.to_vec();
+        tips.sort();
+        tips.dedup();
+        if let Some(reachable) = REACHABLE.lock()?.get(&tips) {
+            return Ok(reachable.clone());
+        }
+
+        let mut walk = self.repo.revwalk()?;
+        for tip in tips.iter() {
+            walk.push(*tip)?;
+        }
+        let reachable =
+            std::sync::Arc::new(walk.collect::<Result<std::collections::HashSet<_>, _>>()?);
+
+        let mut cache = REACHABLE.lock()?;
+        if cache.len() >= REACHABLE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(tips, reachable.clone());
+        Ok(reachable)
+    }
+
     pub fn insert_ref(&self, filter: filter::Filter, from: git2::Oid, to: git2::Oid) {
         REF_CACHE
             .lock()
@@ -292,6 +397,70 @@ impl Transaction {
         return t.len();
     }
 
+    /// Call `visit` with the entries of the forward map of `filter` in the order of
+    /// the original commit id, starting after `after`, until it returns `false`.
+    /// This includes both the stored entries and the ones of this transaction.
+    pub fn mappings(
+        &self,
+        filter: filter::Filter,
+        after: Option<git2::Oid>,
+        visit: &mut dyn FnMut(git2::Oid, git2::Oid) -> bool,
+    ) {
+        let mut t2 = self.t2.borrow_mut();
+        let after = after.unwrap_or(git2::Oid::zero());
+        let mut memory: Vec<(git2::Oid, git2::Oid)> = t2
+            .commit_map
+            .get(&filter.id())
+            .map(|m| {
+                m.iter()
+                    .filter(|(k, _)| **k > after)
+                    .map(|(k, v)| (*k, *v))
+                    .collect()
+            })
+            .unwrap_or_default();
+        memory.sort();
+        let mut memory = memory.into_iter().peekable();
+
+        let t = t2.sled_trees.entry(filter.id()).or_insert_with(|| {
+            DB.lock()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .open_tree(filter::spec(filter))
+                .unwrap()
+        });
+        let mut stored = t
+            .range(after.as_bytes()..)
+            .filter_map(|x| x.ok())
+            .map(|(k, v)| {
+                (
+                    git2::Oid::from_bytes(&k).unwrap(),
+                    git2::Oid::from_bytes(&v).unwrap(),
+                )
+            })
+            .filter(|(k, _)| *k > after)
+            .peekable();
+
+        loop {
+            let next = match (memory.peek(), stored.peek()) {
+                (Some(m), Some(s)) if s.0 < m.0 => stored.next(),
+                (Some(m), Some(s)) => {
+                    if s.0 == m.0 {
+                        stored.next();
+                    }
+                    memory.next()
+                }
+                (Some(_), None) => memory.next(),
+                (None, Some(_)) => stored.next(),
+                (None, None) => None,
+            };
+            let (from, to) = some_or!(next, { break });
+            if !visit(from, to) {
+                break;
+            }
+        }
+    }
+
     pub fn get_missing(&self) -> Vec<(filter::Filter, git2::Oid)> {
         let mut missing = self.t2.borrow().missing.clone();
         missing.sort();
@@ -349,3 +518,22 @@ impl Transaction {
         return None;
     }
 }
+
+/// Cancels the history walks of a transaction when dropped. Request handlers hold it
+/// while the request is executed on another thread, so that the walks stop once the
+/// client disconnects and the request future is dropped.
+pub struct CancelOnDrop(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+impl CancelOnDrop {
+    pub fn new(transaction: &Transaction) -> CancelOnDrop {
+        let token = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        transaction.set_cancel(token.clone());
+        CancelOnDrop(token)
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
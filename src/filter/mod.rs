@@ -5,6 +5,7 @@ mod opt;
 mod parse;
 pub mod tree;
 
+pub use parse::file_entries;
 pub use parse::get_comments;
 pub use parse::parse;
 
@@ -24,6 +25,17 @@ impl Filter {
     }
 }
 
+/// Find a filter by its id. Only filters that have been created within
+/// the running process can be found.
+pub fn from_id(id: git2::Oid) -> Option<Filter> {
+    let filter = Filter(id);
+    if FILTERS.lock().unwrap().contains_key(&filter) {
+        Some(filter)
+    } else {
+        None
+    }
+}
+
 pub fn nop() -> Filter {
     to_filter(Op::Nop)
 }
@@ -60,7 +72,9 @@ enum Op {
     Workspace(std::path::PathBuf),
 
     Glob(String),
+    Grep(String),
 
+    Combine(Vec<std::path::PathBuf>),
     Compose(Vec<Filter>),
     Chain(Filter, Filter),
     Subtract(Filter, Filter),
@@ -111,12 +125,12 @@ fn pretty2(op: &Op, indent: usize, compose: bool) -> String {
         },
         Op::Chain(a, b) => match (to_op(*a), to_op(*b)) {
             (Op::Subdir(p1), Op::Prefix(p2)) if p1 == p2 => {
-                format!("::{}/", p1.to_string_lossy().to_string())
+                format!("::{}", parse::quote(&format!("{}/", p1.to_string_lossy())))
             }
             (a, Op::Prefix(p)) if compose => {
                 format!(
                     "{} = {}",
-                    p.to_string_lossy().to_string(),
+                    parse::quote(&p.to_string_lossy()),
                     pretty2(&a, indent, false)
                 )
             }
@@ -153,12 +167,22 @@ fn spec2(op: &Op) -> String {
             format!(":subtract[{},{}]", spec(*a), spec(*b))
         }
         Op::Workspace(path) => {
-            format!(":workspace={}", path.to_string_lossy())
+            format!(":workspace={}", parse::quote(&path.to_string_lossy()))
+        }
+        Op::Combine(paths) => {
+            format!(
+                ":combine={}",
+                paths
+                    .iter()
+                    .map(|x| parse::quote(&x.to_string_lossy()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
         }
 
         Op::Chain(a, b) => match (to_op(*a), to_op(*b)) {
             (Op::Subdir(p1), Op::Prefix(p2)) if p1 == p2 => {
-                format!("::{}/", p1.to_string_lossy().to_string())
+                format!("::{}", parse::quote(&format!("{}/", p1.to_string_lossy())))
             }
             (a, b) => format!("{}{}", spec2(&a), spec2(&b)),
         },
@@ -168,10 +192,11 @@ fn spec2(op: &Op) -> String {
         Op::Paths => ":PATHS".to_string(),
         Op::Fold => ":FOLD".to_string(),
         Op::Squash => ":SQUASH".to_string(),
-        Op::Subdir(path) => format!(":/{}", path.to_string_lossy()),
-        Op::File(path) => format!("::{}", path.to_string_lossy()),
-        Op::Prefix(path) => format!(":prefix={}", path.to_string_lossy()),
-        Op::Glob(pattern) => format!("::{}", pattern),
+        Op::Subdir(path) => format!(":/{}", parse::quote(&path.to_string_lossy())),
+        Op::File(path) => format!("::{}", parse::quote(&path.to_string_lossy())),
+        Op::Prefix(path) => format!(":prefix={}", parse::quote(&path.to_string_lossy())),
+        Op::Glob(pattern) => format!("::{}", parse::quote(pattern)),
+        Op::Grep(pattern) => format!(":grep={}", parse::quote(pattern)),
     }
 }
 
@@ -189,6 +214,59 @@ fn src_path2(op: &Op) -> std::path::PathBuf {
     .to_owned()
 }
 
+/// Paths of the input tree the output of `filter` can depend on. An empty
+/// path means the whole tree is needed, an empty result that nothing is read.
+/// Paths contained in other paths of the result are omitted.
+pub fn input_paths(filter: Filter) -> Vec<std::path::PathBuf> {
+    let mut paths = input_paths2(&to_op(filter));
+    paths.sort();
+    let mut result: Vec<std::path::PathBuf> = vec![];
+    for path in paths {
+        if !result.iter().any(|x| path.starts_with(x)) {
+            result.push(path);
+        }
+    }
+    result
+}
+
+fn input_paths2(op: &Op) -> Vec<std::path::PathBuf> {
+    let everything = || vec![std::path::PathBuf::new()];
+    match op {
+        Op::Empty => vec![],
+        Op::Subdir(path) | Op::File(path) => vec![path.to_owned()],
+        Op::Combine(paths) => paths.clone(),
+        Op::Glob(pattern) => vec![std::path::Path::new(pattern)
+            .components()
+            .take_while(|c| {
+                !c.as_os_str()
+                    .to_string_lossy()
+                    .contains(&['*', '?', '['][..])
+            })
+            .collect()],
+        Op::Compose(filters) => filters.iter().flat_map(|f| input_paths(*f)).collect(),
+        Op::Subtract(a, b) => input_paths(*a).into_iter().chain(input_paths(*b)).collect(),
+        Op::Chain(a, b) => {
+            let mut paths = vec![];
+            for path in input_paths(*b) {
+                match to_op(*a) {
+                    Op::Subdir(p) => paths.push(normalize_path(&p.join(path))),
+                    Op::Prefix(p) => {
+                        if let Ok(stripped) = path.strip_prefix(&p) {
+                            paths.push(stripped.to_owned());
+                        } else if p.starts_with(&path) {
+                            paths.append(&mut everything());
+                        }
+                    }
+                    Op::Nop | Op::Squash => paths.push(path),
+                    ref a => paths.append(&mut input_paths2(a)),
+                }
+            }
+            paths
+        }
+        _ => everything(),
+    }
+}
+
 pub fn dst_path(filter: Filter) -> std::path::PathBuf {
     dst_path2(&to_op(filter))
 }
@@ -218,7 +296,9 @@ pub fn apply_to_commit(
         }
 
         for (f, i) in transaction.get_missing() {
-            history::walk2(f, i, transaction)?;
+            if history::walk2(f, i, None, transaction)? == history::WalkResult::Cancelled {
+                return Err(josh_error("cancelled"));
+            }
         }
     }
 
@@ -418,6 +498,18 @@ fn apply_to_commit2(
     .transpose();
 }
 
+/// Calculate the filtered tree for the tree with id `tree`.
+/// Unlike `apply_to_commit` no history is walked and no commits are created,
+/// so this is the cheap path when only the content of a single revision is needed.
+pub fn apply_to_tree(
+    filter: Filter,
+    tree: git2::Oid,
+    transaction: &cache::Transaction,
+) -> JoshResult<git2::Oid> {
+    let tree = transaction.repo().find_tree(tree)?;
+    Ok(apply(transaction, filter, tree)?.id())
+}
+
 /// Filter a single tree. This does not involve walking history and is thus fast in most cases.
 pub fn apply<'a>(
     transaction: &'a cache::Transaction,
@@ -454,6 +546,23 @@ fn apply2<'a>(
                 to_filter(op.clone()).id(),
             )
         }
+        Op::Grep(pattern) => {
+            let regex = regex::bytes::Regex::new(pattern)?;
+            tree::remove_pred(
+                transaction,
+                "",
+                tree.id(),
+                &|path, isblob| {
+                    isblob
+                        && tree
+                            .get_path(path)
+                            .and_then(|entry| repo.find_blob(entry.id()))
+                            .map(|blob| !blob.is_binary() && regex.is_match(blob.content()))
+                            .unwrap_or(false)
+                },
+                to_filter(op.clone()).id(),
+            )
+        }
         Op::File(path) => {
             let (file, mode) = tree
                 .get_path(&path)
@@ -495,6 +604,27 @@ fn apply2<'a>(
             }
         }
 
+        Op::Combine(paths) => {
+            let mut sources: Vec<(&std::path::PathBuf, git2::Tree)> = vec![];
+            let mut result = tree::empty_id();
+            for path in paths.iter() {
+                let source = apply2(transaction, &Op::Subdir(path.to_owned()), tree.clone())?;
+                for (other, other_tree) in sources.iter() {
+                    if let Some(collision) =
+                        tree::find_collisions(&repo, other_tree, &source)?.first()
+                    {
+                        return Err(josh_error(&format!(
+                            "combine: {:?} exists in both {:?} and {:?}",
+                            collision, other, path
+                        )));
+                    }
+                }
+                result = tree::overlay(&repo, result, source.id())?;
+                sources.push((path, source));
+            }
+            Ok(repo.find_tree(result)?)
+        }
+
         Op::Compose(filters) => {
             let filtered: Vec<_> = filters
                 .iter()
@@ -586,6 +716,47 @@ fn unapply2<'a>(
             return Ok(result);
         }
 
+        Op::Combine(paths) => {
+            let repo = transaction.repo();
+            let sources = paths
+                .iter()
+                .map(|path| {
+                    apply2(
+                        transaction,
+                        &Op::Subdir(path.to_owned()),
+                        parent_tree.clone(),
+                    )
+                })
+                .collect::<JoshResult<Vec<_>>>()?;
+
+            let mut blobs = vec![];
+            tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+                if let (Some(git2::ObjectType::Blob), Some(name)) = (entry.kind(), entry.name()) {
+                    blobs.push((Path::new(root).join(name), entry.id(), entry.filemode()));
+                }
+                0
+            })?;
+
+            // Each path goes back to the source that already contains it, or the
+            // closest existing parent directory of it. New paths go to the first source.
+            let mut unapplied = vec![tree::empty(&repo); paths.len()];
+            for (path, id, mode) in blobs {
+                let index = path
+                    .ancestors()
+                    .filter(|x| *x != Path::new(""))
+                    .filter_map(|x| sources.iter().position(|s| s.get_path(x).is_ok()))
+                    .next()
+                    .unwrap_or(0);
+                unapplied[index] = tree::insert(&repo, &unapplied[index], &path, id, mode)?;
+            }
+
+            let mut result = parent_tree;
+            for (path, t) in paths.iter().zip(unapplied.iter()) {
+                result = tree::insert(&repo, &result, path, t.id(), 0o0040000)?;
+            }
+            Ok(result)
+        }
+
         Op::File(path) => {
             let (file, mode) = tree
                 .get_path(&path)
@@ -648,6 +819,92 @@ fn unapply2<'a>(
     };
 }
 
+/// Check if `unapply` is supported for `filter`, so changes to the filtered tree
+/// can be mapped back. The filters inside a `:workspace` are not known in advance
+/// and not checked.
+pub fn is_reversible(filter: Filter) -> bool {
+    match to_op(filter) {
+        Op::Nop
+        | Op::Empty
+        | Op::File(_)
+        | Op::Prefix(_)
+        | Op::Subdir(_)
+        | Op::Workspace(_)
+        | Op::Glob(_)
+        | Op::Combine(_) => true,
+        Op::Fold | Op::Squash | Op::Paths | Op::Grep(_) => false,
+        Op::Chain(a, b) => is_reversible(a) && is_reversible(b),
+        Op::Compose(filters) => filters.into_iter().all(is_reversible),
+        Op::Subtract(a, b) => to_filter(Op::Nop) == a && is_reversible(b),
+    }
+}
+
+/// Check if `filter` leaves every tree unchanged. This is the case for `:nop`
+/// but also for chains of filters that cancel each other out, like `:prefix=a:/a`.
+pub fn is_identity(filter: Filter) -> bool {
+    let mut parts = vec![];
+    chain_parts(filter, &mut parts);
+
+    // Prefix added by the filters so far that still has to be removed
+    let mut prefix = std::path::PathBuf::new();
+    for part in parts {
+        match to_op(part) {
+            Op::Nop => {}
+            Op::Prefix(p) => prefix = p.join(&prefix),
+            Op::Subdir(p) => {
+                prefix = some_or!(prefix.strip_prefix(&p).ok(), { return false }).to_owned()
+            }
+            Op::Compose(filters) => {
+                if filters.len() == 0 || !filters.into_iter().all(is_identity) {
+                    return false;
+                }
+            }
+            Op::Subtract(a, b) => {
+                if !is_identity(a) || to_filter(Op::Empty) != b {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+    prefix == std::path::Path::new("")
+}
+
+fn chain_parts(filter: Filter, parts: &mut Vec<Filter>) {
+    if let Op::Chain(a, b) = to_op(filter) {
+        chain_parts(a, parts);
+        chain_parts(b, parts);
+    } else {
+        parts.push(filter);
+    }
+}
+
+/// Create a filter selecting exactly the files at `paths`
+pub fn files(paths: &[std::path::PathBuf]) -> Filter {
+    opt::optimize(to_filter(Op::Compose(
+        paths
+            .iter()
+            .map(|path| to_filter(Op::File(path.to_owned())))
+            .collect(),
+    )))
+}
+
+/// Create a filter taking the subdirectory `path` as root, like `:/path`
+pub fn subdir(path: &Path) -> Filter {
+    if path == Path::new("") {
+        return nop();
+    }
+    opt::optimize(to_filter(Op::Subdir(path.to_owned())))
+}
+
+/// Create a filter moving the tree into the directory `path`, like `:prefix=path`
+pub fn prefix(path: &Path) -> Filter {
+    if path == Path::new("") {
+        return nop();
+    }
+    opt::optimize(to_filter(Op::Prefix(path.to_owned())))
+}
+
 /// Create a filter that is the result of feeding the output of `first` into `second`
 pub fn chain(first: Filter, second: Filter) -> Filter {
     opt::optimize(to_filter(Op::Chain(first, second)))
@@ -658,14 +915,32 @@ pub fn compose(first: Filter, second: Filter) -> Filter {
     opt::optimize(to_filter(Op::Compose(vec![first, second])))
 }
 
-/// Compute the warnings (filters not matching anything) for the filter applied to the tree
+/// Compute the warnings (filters not matching anything, or several filters
+/// mapping different paths to the same output path) for the filter applied to the tree
 pub fn compute_warnings<'a>(
     transaction: &'a cache::Transaction,
     filter: Filter,
     tree: git2::Tree<'a>,
 ) -> Vec<String> {
+    compute_warnings_until(transaction, filter, tree, None).0
+}
+
+/// Like `compute_warnings`, but stop checking once `deadline` has passed.
+/// Filters that match nothing are reported before path collisions, which are
+/// more expensive to find. The second value is `true` if checks were skipped.
+pub fn compute_warnings_until<'a>(
+    transaction: &'a cache::Transaction,
+    filter: Filter,
+    tree: git2::Tree<'a>,
+    deadline: Option<std::time::Instant>,
+) -> (Vec<String>, bool) {
     let mut warnings = Vec::new();
     let mut filter = filter;
+    let expired = || {
+        deadline
+            .map(|d| std::time::Instant::now() > d)
+            .unwrap_or(false)
+    };
 
     if let Op::Workspace(path) = to_op(filter) {
         let workspace_filter = &tree::get_blob(
@@ -677,22 +952,29 @@ pub fn compute_warnings<'a>(
             filter = res;
         } else {
             warnings.push("couldn't parse workspace\n".to_string());
-            return warnings;
+            return (warnings, false);
         }
     }
 
     let filter = opt::flatten(filter);
     if let Op::Compose(filters) = to_op(filter) {
-        for f in filters {
+        for f in filters.iter() {
+            if expired() {
+                return (warnings, true);
+            }
             let tree = transaction.repo().find_tree(tree.id());
             if let Ok(tree) = tree {
-                warnings.append(&mut compute_warnings2(transaction, f, tree));
+                warnings.append(&mut compute_warnings2(transaction, *f, tree));
             }
         }
+        let (mut collisions, truncated) =
+            compute_collisions(transaction, &filters, &tree, &expired);
+        warnings.append(&mut collisions);
+        return (warnings, truncated);
     } else {
         warnings.append(&mut compute_warnings2(transaction, filter, tree));
     }
-    return warnings;
+    return (warnings, false);
 }
 
 fn compute_warnings2<'a>(
@@ -703,19 +985,225 @@ fn compute_warnings2<'a>(
     let mut warnings = Vec::new();
 
     let tree = apply(&transaction, filter, tree);
-    if let Ok(tree) = tree {
-        if tree.is_empty() {
-            warnings.push(format!("No match for \"{}\"", pretty(filter, 2)));
+    match tree {
+        Ok(tree) => {
+            if tree.is_empty() {
+                warnings.push(format!("No match for \"{}\"", pretty(filter, 2)));
+            }
         }
+        Err(JoshError(msg)) => warnings.push(msg),
     }
     return warnings;
 }
 
+fn compute_collisions<'a>(
+    transaction: &'a cache::Transaction,
+    filters: &Vec<Filter>,
+    tree: &git2::Tree<'a>,
+    expired: &dyn Fn() -> bool,
+) -> (Vec<String>, bool) {
+    let mut warnings = Vec::new();
+
+    let outputs: Vec<_> = filters
+        .iter()
+        .filter_map(|f| Some((*f, apply(&transaction, *f, tree.clone()).ok()?)))
+        .collect();
+
+    for (i, (fb, tb)) in outputs.iter().enumerate() {
+        for (fa, ta) in outputs[..i].iter() {
+            if expired() {
+                return (warnings, true);
+            }
+            let collisions = ok_or!(tree::find_collisions(transaction.repo(), ta, tb), {
+                continue;
+            });
+            for path in collisions {
+                let a = tree::original_path(transaction, *fa, tree.clone(), &path);
+                let b = tree::original_path(transaction, *fb, tree.clone(), &path);
+                if let (Ok(a), Ok(b)) = (&a, &b) {
+                    if a == b {
+                        continue;
+                    }
+                }
+                warnings.push(format!(
+                    "Path collision: \"{}\" and \"{}\" both map to \"{}\"",
+                    a.unwrap_or(pretty(*fa, 2)),
+                    b.unwrap_or(pretty(*fb, 2)),
+                    path.to_string_lossy()
+                ));
+            }
+        }
+    }
+    return (warnings, false);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::test_repo;
     use std::path::PathBuf;
 
+    #[test]
+    fn spec_roundtrip_test() {
+        let corpus = [
+            "",
+            ":/",
+            ":nop",
+            ":empty",
+            ":/a",
+            ":/a/b/c",
+            "::a",
+            "::a/b",
+            "::a/",
+            "::a/b/",
+            "::*.txt",
+            "::a/**/*.txt",
+            ":prefix=a",
+            ":prefix=a/b",
+            ":workspace=ws",
+            ":combine=a,b/c",
+            ":SQUASH",
+            ":PATHS",
+            ":FOLD",
+            ":/a:prefix=b",
+            ":/a::b/c:prefix=d",
+            ":[:/a,:/b]",
+            ":[:/a:prefix=x,::b/c]",
+            ":[::a/,:[:/b,:/c]:prefix=d]",
+            ":exclude[::a]",
+            ":exclude[::a,:/b]",
+            ":subtract[:/a,::a/b]",
+            ":/a:exclude[::b]:prefix=c",
+            ":[a=:/x,b/c=:/y]",
+            ":SQUASH:/a",
+            ":/a:FOLD",
+            ":[:/a]",
+            ":[]",
+            ":exclude[]",
+            ":subtract[:/,:/]",
+            "::*",
+            ":/a/",
+            ":/a:/b",
+            ":prefix=a:/a",
+            ":prefix=a:prefix=b",
+            ":/a:empty",
+            ":empty:/a",
+            ":[:/a,:empty]",
+            ":[:/a,:/a]",
+            ":workspace=a:prefix=b",
+            ":combine=a",
+            ":[a=:/x]",
+            ":[a/b=:[:/x,:/y]]",
+            ":nop:nop",
+            "::a/b/c/",
+            r#"::"a b""#,
+            r#"::"a,b""#,
+            r#":[::"a,b",::c]"#,
+            r#":/"x:y""#,
+            r#":prefix="a]b""#,
+            r#"::"q\"uote""#,
+            r#"::"back\\slash""#,
+            r#"::"a b/""#,
+            r#"::"a b/*.txt""#,
+            r#":combine="a b",c"#,
+            r#":workspace="w s""#,
+            r#":["a b"=:/x]"#,
+            ":grep=publish",
+            r#":grep="^// @publish$""#,
+            r#":/a:grep="x,y":prefix=b"#,
+        ];
+
+        let mut specs = std::collections::HashMap::new();
+        for input in corpus.iter() {
+            let filter = parse(input).unwrap();
+            let s = spec(filter);
+            let reparsed = parse(&s).unwrap_or_else(|e| panic!("{:?} -> {:?}: {}", input, s, e));
+            assert_eq!(spec(reparsed), s, "spec of {:?} is not stable", input);
+            assert_eq!(
+                opt::simplify(reparsed),
+                opt::simplify(filter),
+                "{:?} -> {:?} parses to a different filter",
+                input,
+                s
+            );
+            if let Some(other) = specs.insert(s.clone(), opt::simplify(filter)) {
+                assert_eq!(other, opt::simplify(filter), "{:?} is ambiguous", s);
+            }
+            assert_eq!(
+                opt::simplify(parse(&pretty(filter, 0)).unwrap()),
+                opt::simplify(filter),
+                "pretty of {:?}",
+                input
+            );
+        }
+
+        // Filters built from arbitrary paths, for example of changed files
+        let filter = files(&[PathBuf::from("a,b"), PathBuf::from("c d/[e]")]);
+        let reparsed = parse(&spec(filter)).unwrap();
+        assert_eq!(opt::simplify(reparsed), opt::simplify(filter));
+    }
+
+    /// Check that unapplying the unchanged output of the filter `spec` onto the
+    /// tree of `commit` results in exactly that tree again
+    fn assert_roundtrip(transaction: &cache::Transaction, spec: &str, commit: git2::Oid) {
+        let filter = parse(spec).unwrap();
+        assert!(is_reversible(filter), "{} is not reversible", spec);
+        let tree = transaction
+            .repo()
+            .find_commit(commit)
+            .unwrap()
+            .tree()
+            .unwrap();
+        let filtered = apply(transaction, filter, tree.clone()).unwrap();
+        let unapplied = unapply(transaction, filter, filtered, tree.clone()).unwrap();
+        assert_eq!(unapplied.id(), tree.id(), "roundtrip of {}", spec);
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        let repo = test_repo("roundtrip_test");
+        let commit = {
+            let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+            let mut tree = tree::empty(&repo);
+            for (path, content) in [
+                ("a/f", "f"),
+                ("a/g.txt", "g"),
+                ("b/h", "h"),
+                ("b/i/j.txt", "j"),
+                ("c/d/e", "e"),
+                ("ws/workspace.josh", "x = :/a\ny/z = :/c/d\n"),
+            ]
+            .iter()
+            {
+                let blob = repo.blob(content.as_bytes()).unwrap();
+                tree = tree::insert(&repo, &tree, Path::new(path), blob, 0o0100644).unwrap();
+            }
+            repo.commit(None, &signature, &signature, "add files", &tree, &[])
+                .unwrap()
+        };
+        let transaction = cache::Transaction::new(repo, None);
+
+        for spec in [
+            ":nop",
+            ":/a",
+            ":/c/d",
+            "::a/f",
+            "::b/",
+            "::**/*.txt",
+            ":prefix=p",
+            ":/a:prefix=q",
+            ":[x=:/a,y=:/b]",
+            ":[::a/,::c/d/e]",
+            ":exclude[::b/]",
+            ":combine=a,c/d",
+            ":workspace=ws",
+        ]
+        .iter()
+        {
+            assert_roundtrip(&transaction, spec, commit);
+        }
+    }
+
     #[test]
     fn src_path_test() {
         assert_eq!(PathBuf::from("x"), src_path(parse(":/x").unwrap()));
@@ -723,6 +1211,256 @@ mod tests {
         assert_eq!(PathBuf::from("x/y"), src_path(parse(":/x::y").unwrap()));
     }
 
+    #[test]
+    fn is_reversible_test() {
+        assert!(is_reversible(parse(":/x").unwrap()));
+        assert!(is_reversible(parse(":[a=:/x,b=::y/z]:prefix=c").unwrap()));
+        assert!(is_reversible(parse(":exclude[::x/]").unwrap()));
+        assert!(!is_reversible(parse(":/x:SQUASH").unwrap()));
+        assert!(!is_reversible(parse(":[a=:/x,b=:/y:FOLD]").unwrap()));
+        assert!(!is_reversible(parse(":subtract[:/x,:/y]").unwrap()));
+    }
+
+    #[test]
+    fn is_identity_test() {
+        assert!(is_identity(nop()));
+        assert!(is_identity(parse(":/").unwrap()));
+        assert!(is_identity(parse(":prefix=a:/a").unwrap()));
+        assert!(is_identity(parse(":[:nop,:prefix=a/b:/a/b]").unwrap()));
+        assert!(is_identity(parse(":prefix=a:prefix=b:/b/a").unwrap()));
+        assert!(!is_identity(parse(":/a:prefix=a").unwrap()));
+        assert!(!is_identity(parse(":prefix=a:/b").unwrap()));
+        assert!(!is_identity(parse(":[:nop,::a]").unwrap()));
+        assert!(!is_identity(parse(":empty").unwrap()));
+        assert!(!is_identity(parse(":SQUASH").unwrap()));
+    }
+
+    #[test]
+    fn input_paths_test() {
+        let inputs = |spec: &str| -> Vec<String> {
+            input_paths(parse(spec).unwrap())
+                .iter()
+                .map(|x| x.to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(inputs(":/src"), vec!["src"]);
+        assert_eq!(inputs(":/src/lib"), vec!["src/lib"]);
+        assert_eq!(inputs(":/src:prefix=x"), vec!["src"]);
+        assert_eq!(inputs(":prefix=x"), vec![""]);
+        assert_eq!(inputs(":prefix=x:/x/y"), vec!["y"]);
+        assert_eq!(inputs(":prefix=x:/z"), Vec::<String>::new());
+        assert_eq!(inputs(":[a=:/x,b=::y/z,::x/w]"), vec!["x", "y/z"]);
+        assert_eq!(inputs("::src/*/test/*.rs"), vec!["src"]);
+        assert_eq!(inputs(":exclude[::x/]"), vec![""]);
+        assert_eq!(inputs(":empty"), Vec::<String>::new());
+        assert_eq!(inputs(":workspace=ws"), vec![""]);
+        assert_eq!(inputs(":combine=a,b"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn walk_cancel_test() {
+        let repo = test_repo("walk_cancel_test");
+        let mut commits: Vec<git2::Oid> = vec![];
+        {
+            let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+            let mut tree = tree::empty(&repo);
+            for i in 0..3 {
+                let blob = repo.blob(format!("{}", i).as_bytes()).unwrap();
+                tree = tree::insert(&repo, &tree, Path::new("a/f"), blob, 0o0100644).unwrap();
+                let parent = commits.last().map(|id| repo.find_commit(*id).unwrap());
+                let parents: Vec<_> = parent.iter().collect();
+                commits.push(
+                    repo.commit(None, &signature, &signature, "msg", &tree, &parents)
+                        .unwrap(),
+                );
+            }
+        }
+        let transaction = cache::Transaction::new(repo, None);
+        let token = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        transaction.set_cancel(token.clone());
+
+        let filter = parse(":/a").unwrap();
+        token.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(
+            history::walk2(filter, commits[2], None, &transaction).unwrap(),
+            history::WalkResult::Cancelled
+        );
+        assert!(!transaction.known(filter, commits[0]));
+        assert!(transaction.clone().unwrap().cancelled());
+
+        token.store(false, std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(
+            history::walk2(filter, commits[2], None, &transaction).unwrap(),
+            history::WalkResult::Done
+        );
+        for id in commits.iter() {
+            assert!(transaction.known(filter, *id));
+        }
+    }
+
+    #[test]
+    fn walk_hide_test() {
+        let repo = test_repo("walk_hide_test");
+        let mut commits: Vec<git2::Oid> = vec![];
+        {
+            let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+            let mut tree = tree::empty(&repo);
+            for i in 0..5 {
+                let blob = repo.blob(format!("{}", i).as_bytes()).unwrap();
+                let path = format!("{}/f", if i % 2 == 0 { "a" } else { "b" });
+                tree = tree::insert(&repo, &tree, Path::new(&path), blob, 0o0100644).unwrap();
+                let parent = commits.last().map(|id| repo.find_commit(*id).unwrap());
+                let parents: Vec<_> = parent.iter().collect();
+                commits.push(
+                    repo.commit(None, &signature, &signature, "msg", &tree, &parents)
+                        .unwrap(),
+                );
+            }
+        }
+        let transaction = cache::Transaction::new(repo, None);
+        let repo = transaction.repo();
+
+        // Nothing beyond the boundary is walked
+        let filter = parse(":/a").unwrap();
+        history::walk2(filter, commits[4], Some(commits[1]), &transaction).unwrap();
+        assert!(!transaction.known(filter, commits[0]));
+
+        // With the boundary already mapped the remaining commits are filtered
+        let filter = parse("::a/f").unwrap();
+        apply_to_commit(filter, &repo.find_commit(commits[1]).unwrap(), &transaction).unwrap();
+        history::walk2(filter, commits[4], Some(commits[1]), &transaction).unwrap();
+        for id in commits.iter() {
+            assert!(transaction.known(filter, *id));
+        }
+        let head = repo.find_commit(commits[4]).unwrap();
+        let filtered = repo
+            .find_commit(transaction.get(filter, head.id()).unwrap())
+            .unwrap();
+        assert_eq!(
+            filtered.tree_id(),
+            apply(&transaction, filter, head.tree().unwrap())
+                .unwrap()
+                .id()
+        );
+    }
+
+    #[test]
+    fn subdir_strip_test() {
+        let repo = test_repo("subdir_strip_test");
+        let tree = {
+            let mut tree = tree::empty(&repo);
+            for path in ["repo/x", "repo/d/y", "other/z", "top"].iter() {
+                let blob = repo.blob(path.as_bytes()).unwrap();
+                tree = tree::insert(&repo, &tree, Path::new(path), blob, 0o0100644).unwrap();
+            }
+            tree.id()
+        };
+        let transaction = cache::Transaction::new(repo, None);
+        let repo = transaction.repo();
+        let tree = repo.find_tree(tree).unwrap();
+        let paths = |tree: &git2::Tree| {
+            let mut paths = vec![];
+            tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+                if entry.kind() == Some(git2::ObjectType::Blob) {
+                    paths.push(format!("{}{}", root, entry.name().unwrap()));
+                }
+                git2::TreeWalkResult::Ok
+            })
+            .unwrap();
+            paths
+        };
+
+        // Files outside of the stripped directory are dropped
+        let filter = parse(":/repo").unwrap();
+        let filtered = apply(&transaction, filter, tree.clone()).unwrap();
+        assert_eq!(paths(&filtered), ["d/y", "x"]);
+
+        // Unapplying adds the directory back and keeps the files outside of it
+        let blob = repo.blob(b"new").unwrap();
+        let changed = tree::insert(&repo, &filtered, Path::new("n"), blob, 0o0100644).unwrap();
+        let unapplied = unapply(&transaction, filter, changed, tree).unwrap();
+        assert_eq!(
+            paths(&unapplied),
+            ["other/z", "repo/d/y", "repo/n", "repo/x", "top"]
+        );
+    }
+
+    #[test]
+    fn grep_test() {
+        let repo = test_repo("grep_test");
+        let tree = {
+            let mut tree = tree::empty(&repo);
+            for (path, content) in [
+                ("a/public.rs", &b"// @publish\nfn a() {}\n"[..]),
+                ("a/private.rs", b"fn b() {}\n"),
+                ("b/c/public.txt", b"@publish"),
+                ("bin/data", b"@publish\0\x01"),
+                ("private/x", b"nothing"),
+            ]
+            .iter()
+            {
+                let blob = repo.blob(content).unwrap();
+                tree = tree::insert(&repo, &tree, Path::new(path), blob, 0o0100644).unwrap();
+            }
+            tree.id()
+        };
+        let transaction = cache::Transaction::new(repo, None);
+        let tree = transaction.repo().find_tree(tree).unwrap();
+
+        let filter = parse(r#":grep="@publish""#).unwrap();
+        assert!(!is_reversible(filter));
+        let filtered = apply(&transaction, filter, tree.clone()).unwrap();
+        let mut paths = vec![];
+        filtered
+            .walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+                paths.push(format!("{}{}", root, entry.name().unwrap()));
+                git2::TreeWalkResult::Ok
+            })
+            .unwrap();
+        assert_eq!(paths, ["a", "a/public.rs", "b", "b/c", "b/c/public.txt"]);
+        assert!(unapply(&transaction, filter, filtered, tree).is_err());
+
+        assert!(parse(r#":grep="a(""#).is_err());
+    }
+
+    #[test]
+    fn forward_map_key_test() {
+        let repo = test_repo("forward_map_key_test");
+        let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+        let from = repo
+            .commit(
+                None,
+                &signature,
+                &signature,
+                "from",
+                &tree::empty(&repo),
+                &[],
+            )
+            .unwrap();
+        let to = repo
+            .commit(None, &signature, &signature, "to", &tree::empty(&repo), &[])
+            .unwrap();
+        let path = repo.path().to_owned();
+
+        let filters: Vec<_> = [":/a--b", ":/a:/--b", ":prefix=a--b", ":prefix=--b:prefix=a"]
+            .iter()
+            .map(|spec| parse(spec).unwrap())
+            .collect();
+        let specs: std::collections::HashSet<_> = filters.iter().map(|f| spec(*f)).collect();
+        assert_eq!(specs.len(), filters.len());
+
+        for (i, filter) in filters.iter().enumerate() {
+            cache::Transaction::open(&path, None)
+                .unwrap()
+                .insert(*filter, from, to, true);
+            let transaction = cache::Transaction::open(&path, None).unwrap();
+            for (j, other) in filters.iter().enumerate() {
+                let expected = if j <= i { Some(to) } else { None };
+                assert_eq!(transaction.get(*other, from), expected, "{}", spec(*other));
+            }
+        }
+    }
+
     #[test]
     fn dst_path_test() {
         assert_eq!(PathBuf::from(""), dst_path(parse(":/x").unwrap()));
@@ -6,26 +6,71 @@ fn make_op(args: &[&str]) -> JoshResult<Op> {
         ["empty"] => Ok(Op::Empty),
         ["prefix", arg] => Ok(Op::Prefix(Path::new(arg).to_owned())),
         ["workspace", arg] => Ok(Op::Workspace(Path::new(arg).to_owned())),
+        ["grep", arg] => {
+            regex::bytes::Regex::new(arg)?;
+            Ok(Op::Grep(arg.to_string()))
+        }
         ["SQUASH"] => Ok(Op::Squash),
         ["PATHS"] => Ok(Op::Paths),
         ["FOLD"] => Ok(Op::Fold),
+        ["combine", paths @ ..] if paths.len() > 0 => Ok(Op::Combine(
+            paths.iter().map(|p| Path::new(p).to_owned()).collect(),
+        )),
         _ => Err(josh_error("invalid filter")),
     }
 }
 
+/// Quote `s` if it contains characters that are not allowed in unquoted
+/// arguments of filter specs
+pub(crate) fn quote(s: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-+.*/".contains(c);
+    if s != "" && s.chars().all(plain) {
+        return s.to_string();
+    }
+    format!("\"{}\"", s.replace("\\", "\\\\").replace("\"", "\\\""))
+}
+
+/// Value of an argument or path, which is either plain or a quoted string
+fn unquote(pair: pest::iterators::Pair<Rule>) -> String {
+    let s = pair.as_str();
+    let string = if let Rule::string = pair.as_rule() {
+        Some(s)
+    } else {
+        pair.into_inner()
+            .next()
+            .filter(|x| x.as_rule() == Rule::string)
+            .map(|x| x.as_str())
+    };
+    if let Some(string) = string {
+        let mut result = String::new();
+        let mut chars = string[1..string.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            result.push(if c == '\\' {
+                chars.next().unwrap_or(c)
+            } else {
+                c
+            });
+        }
+        result
+    } else {
+        s.to_string()
+    }
+}
+
 fn parse_item(pair: pest::iterators::Pair<Rule>) -> JoshResult<Op> {
     match pair.as_rule() {
         Rule::filter => {
-            let v: Vec<_> = pair.into_inner().map(|x| x.as_str()).collect();
-            make_op(v.as_slice())
+            let v: Vec<_> = pair.into_inner().map(unquote).collect();
+            make_op(&v.iter().map(|x| x.as_str()).collect::<Vec<_>>())
         }
         Rule::filter_nop => Ok(Op::Nop),
         Rule::filter_subdir => Ok(Op::Subdir(
-            Path::new(pair.into_inner().next().unwrap().as_str()).to_owned(),
+            Path::new(&unquote(pair.into_inner().next().unwrap())).to_owned(),
         )),
         Rule::filter_presub => {
             let mut inner = pair.into_inner();
-            let arg = inner.next().unwrap().as_str();
+            let arg = unquote(inner.next().unwrap());
+            let arg = arg.as_str();
             if arg.ends_with("/") {
                 let arg = arg.trim_end_matches("/");
                 Ok(Op::Chain(
@@ -71,13 +116,13 @@ fn parse_file_entry(
     match pair.as_rule() {
         Rule::file_entry => {
             let mut inner = pair.into_inner();
-            let path = inner.next().unwrap().as_str();
+            let path = unquote(inner.next().unwrap());
             let filter = inner
                 .next()
                 .map(|x| x.as_str().to_owned())
                 .unwrap_or(format!(":/{}", path));
             let filter = parse(&filter)?;
-            let filter = chain(filter, to_filter(Op::Prefix(Path::new(path).to_owned())));
+            let filter = chain(filter, to_filter(Op::Prefix(Path::new(&path).to_owned())));
             filters.push(filter);
             Ok(())
         }
@@ -169,6 +214,42 @@ pub fn parse(filter_spec: &str) -> JoshResult<Filter> {
     )?))));
 }
 
+/// Entries of a workspace file as pairs of the destination path and the filter
+/// spec, in the order they appear in the file. The specs are not parsed, so an
+/// invalid spec only fails once it is used.
+pub fn file_entries(filter_spec: &str) -> JoshResult<Vec<(String, String)>> {
+    match Grammar::parse(Rule::workspace_file, filter_spec) {
+        Ok(mut r) => {
+            let r = r.next().unwrap();
+            let mut entries = vec![];
+            for pair in r.into_inner() {
+                if pair.as_rule() != Rule::compose {
+                    continue;
+                }
+                for entry in pair.into_inner() {
+                    if entry.as_rule() == Rule::file_entry {
+                        let mut inner = entry.into_inner();
+                        let path = unquote(inner.next().unwrap());
+                        let spec = inner
+                            .next()
+                            .map(|x| x.as_str().to_owned())
+                            .unwrap_or_default();
+                        entries.push((path, spec));
+                    }
+                }
+            }
+            return Ok(entries);
+        }
+        Err(r) => {
+            return Err(josh_error(&format!(
+                "Invalid workspace:\n----\n{}\n\n{}\n----",
+                r.to_string().replace("␊", ""),
+                filter_spec
+            )));
+        }
+    }
+}
+
 /// Get the potential leading comments from a workspace.josh as a string
 pub fn get_comments(filter_spec: &str) -> JoshResult<String> {
     if let Ok(r) = Grammar::parse(Rule::workspace_file, filter_spec) {
@@ -253,6 +253,29 @@ pub fn overlay(
     return Ok(input1);
 }
 
+/// Find all paths that exist in both `tree1` and `tree2` and are not a directory
+/// in both of them, so that overlaying the trees would lose content.
+pub fn find_collisions(
+    repo: &git2::Repository,
+    tree1: &git2::Tree,
+    tree2: &git2::Tree,
+) -> super::JoshResult<Vec<std::path::PathBuf>> {
+    let mut collisions = vec![];
+    for entry in tree2.iter() {
+        let name = entry.name().ok_or(super::josh_error("no name"))?;
+        if let Some(e) = tree1.get_name(name) {
+            if let (Ok(t1), Ok(t2)) = (repo.find_tree(e.id()), repo.find_tree(entry.id())) {
+                for path in find_collisions(repo, &t1, &t2)? {
+                    collisions.push(std::path::Path::new(name).join(path));
+                }
+            } else {
+                collisions.push(std::path::PathBuf::from(name));
+            }
+        }
+    }
+    Ok(collisions)
+}
+
 pub fn pathline(b: &str) -> JoshResult<String> {
     for line in b.split("\n") {
         let l = line.trim_start_matches("#");
@@ -322,6 +345,101 @@ pub fn original_path(
     return pathline(&b);
 }
 
+/// Where the file at `path` in `tree` ends up after applying `filter`,
+/// or `None` if it is not part of the output. The inverse of `original_path`.
+pub fn filtered_path(
+    transaction: &cache::Transaction,
+    filter: Filter,
+    tree: git2::Tree,
+    path: &std::path::Path,
+) -> JoshResult<Option<String>> {
+    let paths_tree = apply(transaction, chain(to_filter(Op::Paths), filter), tree)?;
+    let ipaths = invert_paths(transaction, "", paths_tree)?;
+    let entry = some_or!(ipaths.get_path(path).ok(), { return Ok(None) });
+    if entry.kind() != Some(git2::ObjectType::Blob) {
+        return Ok(None);
+    }
+    let b = tree::get_blob(transaction.repo(), &ipaths, path);
+    return Ok(Some(pathline(&b)?));
+}
+
+/// Effective git attributes of the file at `path` in the output of `filter`, as
+/// defined by the `.gitattributes` files of the unfiltered `tree`. The result
+/// is in `.gitattributes` syntax ("text", "-diff", "eol=lf"), so it can be
+/// written to the root of the filtered tree to keep the behaviour of the file.
+pub fn attributes(
+    transaction: &cache::Transaction,
+    filter: Filter,
+    tree: git2::Tree,
+    path: &std::path::Path,
+) -> JoshResult<Vec<String>> {
+    let repo = transaction.repo();
+    let original = std::path::PathBuf::from(if filter == nop() {
+        path.to_string_lossy().to_string()
+    } else {
+        original_path(transaction, filter, tree.clone(), path)?
+    });
+    let options = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    let mut attributes: Vec<(String, String)> = vec![];
+    let mut dirs: Vec<_> = original.ancestors().skip(1).collect();
+    dirs.reverse();
+    for dir in dirs {
+        let content = get_blob(&repo, &tree, &dir.join(".gitattributes"));
+        let relative = original.strip_prefix(dir)?;
+        let name = std::path::Path::new(original.file_name().unwrap_or_default());
+        for line in content.lines() {
+            let mut tokens = line.split_whitespace();
+            let pattern = some_or!(tokens.next(), { continue });
+            if pattern.starts_with("#") || pattern.starts_with("[attr]") {
+                continue;
+            }
+            let (pattern, target) = if pattern.trim_start_matches("/").contains("/") {
+                (pattern.trim_start_matches("/"), relative)
+            } else {
+                (pattern, name)
+            };
+            // Like git, skip lines with a pattern that can't be parsed
+            let glob = ok_or!(glob::Pattern::new(pattern), {
+                tracing::warn!(
+                    "attributes: invalid pattern {:?} in {:?}",
+                    pattern,
+                    dir.join(".gitattributes")
+                );
+                continue;
+            });
+            if !glob.matches_path_with(target, options) {
+                continue;
+            }
+            for token in tokens {
+                let (name, state) = if token.starts_with("-") || token.starts_with("!") {
+                    (&token[1..], &token[..1])
+                } else if let Some(i) = token.find("=") {
+                    (&token[..i], &token[i..])
+                } else {
+                    (token, "")
+                };
+                attributes.retain(|(n, _)| n != name);
+                if state != "!" {
+                    attributes.push((name.to_string(), state.to_string()));
+                }
+            }
+        }
+    }
+
+    Ok(attributes
+        .into_iter()
+        .map(|(name, state)| match state.as_str() {
+            "-" => format!("-{}", name),
+            _ => format!("{}{}", name, state),
+        })
+        .collect())
+}
+
 pub fn repopulated_tree(
     transaction: &cache::Transaction,
     filter: Filter,
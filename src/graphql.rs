@@ -8,42 +8,275 @@ pub struct Revision {
     commit_id: git2::Oid,
 }
 
+/// Arguments of `Revision.files`, `Revision.dirs` and their pages
+struct PathQuery {
+    kind: git2::ObjectType,
+    at: Option<String>,
+    depth: Option<i32>,
+    sort: bool,
+    strict: Option<bool>,
+    glob: Option<String>,
+}
+
+impl Revision {
+    /// Paths of the filtered tree matching `query`
+    fn paths(&self, context: &Context, query: PathQuery) -> JoshResult<Vec<Path>> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+        let tree_id = tree.id();
+
+        let paths = find_paths(
+            &transaction,
+            tree,
+            query.at,
+            query.depth,
+            query.kind,
+            query.sort,
+            query.strict.unwrap_or(true),
+            query.glob,
+        )?;
+
+        Ok(paths
+            .into_iter()
+            .map(|path| Path {
+                path: path,
+                commit_id: self.commit_id,
+                filter: self.filter,
+                tree: tree_id,
+            })
+            .collect())
+    }
+
+    /// At most `first` of the sorted paths matching `query` that follow the path `after`
+    fn path_page(
+        &self,
+        context: &Context,
+        query: PathQuery,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> JoshResult<PathPage> {
+        let mut items = self.paths(
+            context,
+            PathQuery {
+                sort: true,
+                ..query
+            },
+        )?;
+        if let Some(after) = after {
+            let after = std::path::PathBuf::from(after);
+            items.retain(|item| item.path > after);
+        }
+        let mut has_more = false;
+        if let Some(first) = first {
+            let first = std::cmp::max(first, 0) as usize;
+            has_more = items.len() > first;
+            items.truncate(first);
+        }
+        Ok(PathPage {
+            items: items,
+            has_more: has_more,
+        })
+    }
+
+    fn filtered_commit<'a>(
+        &self,
+        transaction: &'a cache::Transaction,
+    ) -> JoshResult<git2::Commit<'a>> {
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+        Ok(transaction.repo().find_commit(filtered)?)
+    }
+
+    fn filtered_parent_count(&self, context: &Context) -> JoshResult<usize> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+        let count = transaction.repo().find_commit(filtered)?.parent_count();
+        Ok(count)
+    }
+}
+
+/// Glob matching as done by the `:glob` filter, `*` does not match `/`
+const GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: true,
+};
+
+/// Seconds since the epoch of an RFC3339 `time`
+fn parse_time(time: &str) -> JoshResult<i64> {
+    Ok(chrono::DateTime::parse_from_rfc3339(time)
+        .map_err(|e| josh_error(&format!("invalid time {:?}: {}", time, e)))?
+        .timestamp())
+}
+
 fn find_paths(
     transaction: &cache::Transaction,
     tree: git2::Tree,
     at: Option<String>,
     depth: Option<i32>,
     kind: git2::ObjectType,
+    sort: bool,
+    strict: bool,
+    glob: Option<String>,
 ) -> JoshResult<Vec<std::path::PathBuf>> {
-    let tree = if let Some(at) = at.as_ref() {
-        if at == "" {
-            tree
-        } else {
-            let path = std::path::Path::new(&at).to_owned();
-            transaction.repo().find_tree(tree.get_path(&path)?.id())?
+    let glob = glob
+        .map(|glob| {
+            glob::Pattern::new(&glob)
+                .map_err(|e| josh_error(&format!("invalid glob {:?}: {}", glob, e.msg)))
+        })
+        .transpose()?;
+
+    // Paths are walked relative to the subtree at `at` and then joined with
+    // `base`, so the results are always relative to the root of `tree`
+    let (tree, base) = match at.as_ref().map(|at| at.as_str()) {
+        None | Some("") => (tree, std::path::PathBuf::new()),
+        Some(at) => {
+            let base = std::path::Path::new(at).to_owned();
+            let subtree = tree
+                .get_path(&base)
+                .and_then(|entry| transaction.repo().find_tree(entry.id()));
+            match subtree {
+                Ok(subtree) => (subtree, base),
+                Err(_) if !strict => return Ok(vec![]),
+                Err(_) => return Err(josh_error(&format!("not a tree: {:?}", at))),
+            }
         }
+    };
+
+    let ws = if is_large_tree(transaction.repo(), &tree) {
+        walk_paths_parallel(transaction.repo(), &tree, depth, kind)?
     } else {
-        tree
+        walk_paths(&tree, std::path::Path::new(""), depth, kind)?
     };
 
-    let base = std::path::Path::new(&at.as_ref().unwrap_or(&"".to_string())).to_owned();
+    let mut ws: Vec<_> = ws
+        .into_iter()
+        .filter(|path| {
+            glob.as_ref()
+                .map(|glob| glob.matches_path_with(path, GLOB_OPTIONS))
+                .unwrap_or(true)
+        })
+        .map(|path| base.join(path))
+        .collect();
+    if sort {
+        // `Path` compares by components, so "a/b" sorts before "a.b"
+        ws.sort();
+    }
+    return Ok(ws);
+}
+
+/// Trees with more entries than this in their first two levels are walked in parallel
+const PARALLEL_WALK_THRESHOLD: usize = 10000;
+
+fn is_large_tree(repo: &git2::Repository, tree: &git2::Tree) -> bool {
+    // With a single CPU opening additional repos is only overhead
+    if num_cpus::get() < 2 {
+        return false;
+    }
+    let mut entries = tree.len();
+    for entry in tree.iter() {
+        if let Ok(subtree) = repo.find_tree(entry.id()) {
+            entries += subtree.len();
+        }
+        if entries > PARALLEL_WALK_THRESHOLD {
+            return true;
+        }
+    }
+    false
+}
 
+fn walk_paths(
+    tree: &git2::Tree,
+    root: &std::path::Path,
+    depth: Option<i32>,
+    kind: git2::ObjectType,
+) -> JoshResult<Vec<std::path::PathBuf>> {
     let mut ws = vec![];
-    tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+    tree.walk(git2::TreeWalkMode::PreOrder, |r, entry| {
         if Some(kind) == entry.kind() {
             if let Some(name) = entry.name() {
-                let path = std::path::Path::new(root).join(name);
+                let path = root.join(r).join(name);
                 if let Some(limit) = depth {
                     if path.components().count() as i32 > limit {
                         return 1;
                     }
                 }
-                ws.push(base.join(path));
+                ws.push(path);
             }
         }
         0
     })?;
-    return Ok(ws);
+    Ok(ws)
+}
+
+/// Same as `walk_paths` on the root, but with the top level subtrees distributed
+/// over several threads. The result is in the same order.
+fn walk_paths_parallel(
+    repo: &git2::Repository,
+    tree: &git2::Tree,
+    depth: Option<i32>,
+    kind: git2::ObjectType,
+) -> JoshResult<Vec<std::path::PathBuf>> {
+    let mut results = vec![];
+    let mut jobs = vec![];
+    for entry in tree.iter() {
+        let name = std::path::PathBuf::from(entry.name().ok_or(josh_error("no name"))?);
+        let mut paths = vec![];
+        let mut descend = entry.kind() == Some(git2::ObjectType::Tree);
+        if Some(kind) == entry.kind() {
+            if depth.map(|limit| limit < 1).unwrap_or(false) {
+                descend = false;
+            } else {
+                paths.push(name.clone());
+            }
+        }
+        if descend {
+            jobs.push((results.len(), name, entry.id()));
+        }
+        results.push(paths);
+    }
+
+    // `git2::Repository` can't be shared between threads, so each one opens its own.
+    // Those only see the on-disk object database, so subtrees that exist only
+    // in an in-memory backend of `repo` are handed back and walked here instead
+    let chunk_size = jobs.len().div_ceil(num_cpus::get());
+    let handles: Vec<_> = jobs
+        .chunks(std::cmp::max(chunk_size, 1))
+        .map(|chunk| {
+            let chunk = chunk.to_vec();
+            let path = repo.path().to_owned();
+            std::thread::spawn(move || -> JoshResult<Vec<_>> {
+                let repo = git2::Repository::open(path)?;
+                Ok(chunk
+                    .into_iter()
+                    .map(|(i, name, id)| {
+                        let walked = repo
+                            .find_tree(id)
+                            .ok()
+                            .and_then(|tree| walk_paths(&tree, &name, depth, kind).ok());
+                        (i, name, id, walked)
+                    })
+                    .collect())
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let walked = handle
+            .join()
+            .map_err(|_| josh_error("walk_paths_parallel: thread panicked"))??;
+        for (i, name, id, paths) in walked {
+            let mut paths = match paths {
+                Some(paths) => paths,
+                None => walk_paths(&repo.find_tree(id)?, &name, depth, kind)?,
+            };
+            results[i].append(&mut paths);
+        }
+    }
+
+    Ok(results.concat())
 }
 
 #[graphql_object(context = Context)]
@@ -52,6 +285,17 @@ impl Revision {
         filter::spec(self.filter)
     }
 
+    /// Whether the filter of this revision changes the content, `false` for
+    /// `:nop` and filters equivalent to it
+    fn filtered(&self) -> bool {
+        !filter::is_identity(self.filter)
+    }
+
+    /// Opaque handle of the filter that can be passed to `Repository.rev`
+    fn filter_id(&self) -> String {
+        self.filter.id().to_string()
+    }
+
     fn hash(&self, context: &Context) -> FieldResult<String> {
         let transaction = context.transaction.lock()?;
         let commit = transaction.repo().find_commit(self.commit_id)?;
@@ -59,6 +303,27 @@ impl Revision {
         Ok(format!("{}", filter_commit))
     }
 
+    /// Id of the filtered tree (or its subdirectory `at`), independent of commit metadata
+    fn tree_hash(&self, at: Option<String>, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+
+        let at = at.unwrap_or_default();
+        if at == "" {
+            return Ok(format!("{}", tree.id()));
+        }
+
+        let path = std::path::Path::new(&at);
+        let error = |msg: &str| path_error(msg, &path, self.filter, self.commit_id);
+        let entry = tree.get_path(&path).map_err(|e| error(e.message()))?;
+        if let Some(git2::ObjectType::Tree) = entry.kind() {
+            Ok(format!("{}", entry.id()))
+        } else {
+            Err(error("not a tree"))
+        }
+    }
+
     fn summary(&self, context: &Context) -> FieldResult<String> {
         let transaction = context.transaction.lock()?;
         let commit = transaction.repo().find_commit(self.commit_id)?;
@@ -70,7 +335,66 @@ impl Revision {
         Ok(filter_commit.summary().unwrap_or("").to_owned())
     }
 
-    fn date(&self, format: String, context: &Context) -> FieldResult<String> {
+    /// Full message of the filtered commit, including trailers
+    fn message(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        Ok(commit.message().unwrap_or("").to_owned())
+    }
+
+    /// Message of the filtered commit without the summary line and the blank lines after it
+    fn body(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        let message = commit.message().unwrap_or("");
+        let body = message.splitn(2, '\n').nth(1).unwrap_or("");
+        Ok(body
+            .trim_start_matches(|c| c == '\n' || c == '\r')
+            .to_owned())
+    }
+
+    /// `key: value` lines of the trailer block that ends the filtered commit message
+    fn trailers(&self, context: &Context) -> FieldResult<Vec<Trailer>> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        Ok(parse_trailers(commit.message().unwrap_or("")))
+    }
+
+    /// Name of the author of the filtered commit, empty if it is not valid UTF-8
+    fn author_name(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        let name = commit.author().name().unwrap_or("").to_owned();
+        Ok(name)
+    }
+
+    /// Email of the author of the filtered commit, empty if it is not valid UTF-8
+    fn author_email(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        let email = commit.author().email().unwrap_or("").to_owned();
+        Ok(email)
+    }
+
+    /// Name of the committer of the filtered commit, empty if it is not valid UTF-8
+    fn committer_name(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        let name = commit.committer().name().unwrap_or("").to_owned();
+        Ok(name)
+    }
+
+    /// Email of the committer of the filtered commit, empty if it is not valid UTF-8
+    fn committer_email(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        let email = commit.committer().email().unwrap_or("").to_owned();
+        Ok(email)
+    }
+
+    /// Commit time of the filtered commit formatted with the strftime style `format`,
+    /// in the timezone of the commit, or in UTC with `utc: true`
+    fn date(&self, format: String, utc: Option<bool>, context: &Context) -> FieldResult<String> {
         let transaction = context.transaction.lock()?;
         let commit = transaction.repo().find_commit(self.commit_id)?;
         let filter_commit = transaction.repo().find_commit(filter::apply_to_commit(
@@ -79,10 +403,55 @@ impl Revision {
             &transaction,
         )?)?;
 
-        let ts = filter_commit.time().seconds();
+        let time = filter_commit.time();
+        let offset = if utc.unwrap_or(false) {
+            0
+        } else {
+            time.offset_minutes() * 60
+        };
 
-        let ndt = chrono::NaiveDateTime::from_timestamp(ts, 0);
-        Ok(ndt.format(&format).to_string())
+        let offset = chrono::FixedOffset::east_opt(offset)
+            .ok_or(josh_error(&format!("invalid timezone offset {}", offset)))?;
+        let date = chrono::TimeZone::timestamp_opt(&offset, time.seconds(), 0)
+            .single()
+            .ok_or_else(|| josh_error("invalid commit time"))?;
+        Ok(date.format(&format).to_string())
+    }
+
+    /// Time since the filtered commit in words, like "3 days ago". `from` is the
+    /// RFC 3339 time to compare to instead of now.
+    fn age(&self, from: Option<String>, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filter_commit = transaction.repo().find_commit(filter::apply_to_commit(
+            self.filter,
+            &commit,
+            &transaction,
+        )?)?;
+
+        let now = if let Some(from) = from {
+            parse_time(&from)?
+        } else {
+            chrono::Utc::now().timestamp()
+        };
+        let duration = chrono::Duration::seconds(filter_commit.time().seconds() - now);
+        Ok(chrono_humanize::HumanTime::from(duration).to_string())
+    }
+
+    /// Whether the filtered commit was committed strictly after the RFC3339 `time`
+    fn committed_after(&self, time: String, context: &Context) -> FieldResult<bool> {
+        let time = parse_time(&time)?;
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        Ok(commit.time().seconds() > time)
+    }
+
+    /// Whether the filtered commit was committed strictly before the RFC3339 `time`
+    fn committed_before(&self, time: String, context: &Context) -> FieldResult<bool> {
+        let time = parse_time(&time)?;
+        let transaction = context.transaction.lock()?;
+        let commit = self.filtered_commit(&transaction)?;
+        Ok(commit.time().seconds() < time)
     }
 
     fn rev(
@@ -111,12 +480,267 @@ impl Revision {
         };
 
         Ok(Some(Revision {
-            filter: filter::parse(&filter.unwrap_or(":/".to_string()))?,
+            filter: context.parse_filter(&filter.unwrap_or(":/".to_string()))?,
             commit_id: id,
         }))
     }
 
-    fn parents(&self, context: &Context) -> FieldResult<Vec<Revision>> {
+    /// Ids of all original commits that map to the same filtered commit as this one
+    fn originals(&self, context: &Context) -> FieldResult<Vec<String>> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+
+        Ok(
+            history::find_originals(&transaction, self.filter, self.commit_id, filtered)?
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+        )
+    }
+
+    /// Whether the filtered tree differs from the one of the first parent, or is
+    /// not empty for a commit without parents. Only trees are filtered, not commits.
+    fn touches_filter(&self, context: &Context) -> FieldResult<bool> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+        let parent_tree = if let Ok(parent) = commit.parent(0) {
+            filter::apply(&transaction, self.filter, parent.tree()?)?
+        } else {
+            filter::tree::empty(transaction.repo())
+        };
+        Ok(tree.id() != parent_tree.id())
+    }
+
+    /// Same as this revision, but limited to the files changed since `since`
+    /// in the filtered history
+    fn changed_subset(&self, since: String, context: &Context) -> FieldResult<Revision> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let since = repo.find_commit(resolve_in_namespace(context, &transaction, &since)?)?;
+
+        let old_tree = filter::apply(&transaction, self.filter, since.tree()?)?;
+        let new_tree = filter::apply(
+            &transaction,
+            self.filter,
+            repo.find_commit(self.commit_id)?.tree()?,
+        )?;
+
+        let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+        let paths: Vec<_> = diff
+            .deltas()
+            .filter(|delta| delta.status() != git2::Delta::Deleted)
+            .filter_map(|delta| delta.new_file().path().map(|x| x.to_owned()))
+            .collect();
+
+        Ok(Revision {
+            filter: filter::chain(self.filter, filter::files(&paths)),
+            commit_id: self.commit_id,
+        })
+    }
+
+    /// Changes of the filtered commit compared to its first parent, or to the filtered
+    /// tree of `against` if given. Commits without parents are compared to an empty tree.
+    fn diff(&self, against: Option<String>, context: &Context) -> FieldResult<Vec<FileDiff>> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let commit = repo.find_commit(self.commit_id)?;
+        let new_tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+
+        let old_tree = if let Some(against) = against {
+            let against =
+                repo.find_commit(resolve_in_namespace(context, &transaction, &against)?)?;
+            filter::apply(&transaction, self.filter, against.tree()?)?
+        } else {
+            let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+            match repo.find_commit(filtered).and_then(|c| c.parent(0)) {
+                Ok(parent) => parent.tree()?,
+                Err(_) => filter::tree::empty(repo),
+            }
+        };
+
+        let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+        let mut files = vec![];
+        for (i, delta) in diff.deltas().enumerate() {
+            let path = |file: git2::DiffFile| {
+                if file.id().is_zero() {
+                    None
+                } else {
+                    file.path().map(|p| p.to_string_lossy().to_string())
+                }
+            };
+            let mut patch = some_or!(git2::Patch::from_diff(&diff, i)?, { continue });
+            let (_, insertions, deletions) = patch.line_stats()?;
+            files.push(FileDiff {
+                old_path: path(delta.old_file()),
+                new_path: path(delta.new_file()),
+                insertions: insertions as i32,
+                deletions: deletions as i32,
+                patch: String::from_utf8_lossy(&patch.to_buf()?).to_string(),
+            });
+        }
+        Ok(files)
+    }
+
+    /// Paths that differ between the filtered trees of `against` and this revision.
+    /// Without `against` the first parent of the original commit is used, or an empty
+    /// tree for a root commit. Deleted paths refer to the tree of `against`.
+    fn changed_paths(
+        &self,
+        against: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Vec<ChangedPath>> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let commit = repo.find_commit(self.commit_id)?;
+        let new_tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+
+        let against = if let Some(against) = against {
+            Some(repo.find_commit(resolve_in_namespace(context, &transaction, &against)?)?)
+        } else {
+            commit.parent(0).ok()
+        };
+        let (old_commit_id, old_tree) = match against {
+            Some(against) => (
+                against.id(),
+                filter::apply(&transaction, self.filter, against.tree()?)?,
+            ),
+            None => (self.commit_id, filter::tree::empty(repo)),
+        };
+
+        let mut diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+        diff.find_similar(None)?;
+
+        let mut paths = vec![];
+        for delta in diff.deltas() {
+            let (status, commit_id, tree, file) = match delta.status() {
+                git2::Delta::Added => ("added", self.commit_id, new_tree.id(), delta.new_file()),
+                git2::Delta::Deleted => ("deleted", old_commit_id, old_tree.id(), delta.old_file()),
+                git2::Delta::Renamed => {
+                    ("renamed", self.commit_id, new_tree.id(), delta.new_file())
+                }
+                _ => ("modified", self.commit_id, new_tree.id(), delta.new_file()),
+            };
+            let path = some_or!(file.path(), { continue }).to_owned();
+            let old_path = if delta.status() == git2::Delta::Renamed {
+                delta
+                    .old_file()
+                    .path()
+                    .map(|p| p.to_string_lossy().to_string())
+            } else {
+                None
+            };
+            paths.push(ChangedPath {
+                status: status.to_string(),
+                path: Path {
+                    path: path,
+                    commit_id: commit_id,
+                    filter: self.filter,
+                    tree: tree,
+                },
+                old_path: old_path,
+            });
+        }
+        Ok(paths)
+    }
+
+    /// Whether this commit is `other` or one of its ancestors. This compares the
+    /// original commits, so the result does not depend on the filter.
+    fn reachable_from(&self, other: String, context: &Context) -> FieldResult<bool> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let other = resolve_in_namespace(context, &transaction, &other)?;
+        Ok(other == self.commit_id || repo.graph_descendant_of(other, self.commit_id)?)
+    }
+
+    /// Summary of the commits in the filtered history between `since` and this
+    /// revision, like `git log --numstat since..` would show it. Merge commits are
+    /// counted but their changes are not, as they are part of the merged commits.
+    fn range_stats(&self, since: String, context: &Context) -> FieldResult<RangeStats> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let since = repo.find_commit(resolve_in_namespace(context, &transaction, &since)?)?;
+        let since = filter::apply_to_commit(self.filter, &since, &transaction)?;
+        let head = filter::apply_to_commit(
+            self.filter,
+            &repo.find_commit(self.commit_id)?,
+            &transaction,
+        )?;
+
+        let mut stats = RangeStats {
+            commits: 0,
+            files_changed: 0,
+            insertions: 0,
+            deletions: 0,
+        };
+        if head == git2::Oid::zero() {
+            return Ok(stats);
+        }
+
+        let mut walk = repo.revwalk()?;
+        walk.push(head)?;
+        if since != git2::Oid::zero() {
+            walk.hide(since)?;
+        }
+
+        let mut files = std::collections::HashSet::new();
+        for id in walk {
+            let commit = repo.find_commit(id?)?;
+            stats.commits += 1;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let parent_tree = commit.parents().next().map(|p| p.tree()).transpose()?;
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            let diff_stats = diff.stats()?;
+            stats.insertions += diff_stats.insertions() as i32;
+            stats.deletions += diff_stats.deletions() as i32;
+            for delta in diff.deltas() {
+                for file in [delta.old_file(), delta.new_file()].iter() {
+                    if let Some(path) = file.path() {
+                        files.insert(path.to_owned());
+                    }
+                }
+            }
+        }
+        stats.files_changed = files.len() as i32;
+
+        Ok(stats)
+    }
+
+    /// Text of the git note in the notes ref `ref` (default "refs/notes/commits")
+    /// attached to the original commit this filtered commit was created from
+    fn notes(&self, r#ref: Option<String>, context: &Context) -> FieldResult<Option<String>> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+        let original = history::find_original(&transaction, self.filter, self.commit_id, filtered)?;
+
+        let notes_ref = transaction.refname(&r#ref.unwrap_or("refs/notes/commits".to_string()));
+        let text = match transaction.repo().find_note(Some(&notes_ref), original) {
+            Ok(note) => note.message().map(|x| x.to_string()),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => Err(e)?,
+        };
+        Ok(text)
+    }
+
+    /// Number of parents of the filtered commit. Filtering can drop parents
+    /// that don't contribute to the filtered history, so this can be less
+    /// than the number of parents of the original commit.
+    fn parent_count(&self, context: &Context) -> FieldResult<i32> {
+        Ok(self.filtered_parent_count(context)? as i32)
+    }
+
+    /// Whether the filtered commit has more than one parent
+    fn is_merge(&self, context: &Context) -> FieldResult<bool> {
+        Ok(self.filtered_parent_count(context)? > 1)
+    }
+
+    /// Parents of the filtered commit, at most `limit` of them if given. It is an
+    /// error if no original commit can be found for one of the returned parents.
+    fn parents(&self, limit: Option<i32>, context: &Context) -> FieldResult<Vec<Revision>> {
         let transaction = context.transaction.lock()?;
         let commit = transaction.repo().find_commit(self.commit_id)?;
         let filter_commit = transaction.repo().find_commit(filter::apply_to_commit(
@@ -125,66 +749,342 @@ impl Revision {
             &transaction,
         )?)?;
 
-        let parents = filter_commit
+        let ids: Vec<_> = filter_commit
             .parent_ids()
-            .map(|id| Revision {
-                filter: self.filter,
-                commit_id: history::find_original(&transaction, self.filter, self.commit_id, id)
-                    .unwrap_or(git2::Oid::zero()),
-            })
+            .take(
+                limit
+                    .map(|l| std::cmp::max(l, 0) as usize)
+                    .unwrap_or(usize::MAX),
+            )
             .collect();
+        let originals =
+            history::find_original_batch(&transaction, self.filter, self.commit_id, &ids)?;
+
+        let mut parents = vec![];
+        for (id, original) in ids.iter().zip(originals) {
+            let original = original.ok_or(josh_error(&format!(
+                "no original commit found for filtered parent {}",
+                id
+            )))?;
+            parents.push(Revision {
+                filter: self.filter,
+                commit_id: original,
+            });
+        }
 
         Ok(parents)
     }
 
-    fn files(
+    /// Commits of the filtered history, newest first, whose filtered message matches
+    /// the regex `pattern`, at most `limit` of them if given. This only queries the
+    /// history, the filter is not changed. Like for `parents` it is an error if no
+    /// original commit can be found for one of them.
+    fn history_matching(
+        &self,
+        pattern: String,
+        limit: Option<i32>,
+        context: &Context,
+    ) -> FieldResult<Vec<Revision>> {
+        let regex = regex::Regex::new(&pattern)
+            .map_err(|e| josh_error(&format!("invalid regex {:?}: {}", pattern, e)))?;
+        let limit = limit
+            .map(|l| std::cmp::max(l, 0) as usize)
+            .unwrap_or(usize::MAX);
+
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+
+        let mut ids = vec![];
+        if filtered != git2::Oid::zero() {
+            let mut walk = transaction.repo().revwalk()?;
+            walk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+            walk.push(filtered)?;
+            for id in walk {
+                if ids.len() >= limit {
+                    break;
+                }
+                let id = id?;
+                let filtered_commit = transaction.repo().find_commit(id)?;
+                if regex.is_match(&String::from_utf8_lossy(filtered_commit.message_bytes())) {
+                    ids.push(id);
+                }
+            }
+        }
+        let originals =
+            history::find_original_batch(&transaction, self.filter, self.commit_id, &ids)?;
+
+        let mut revisions = vec![];
+        for (id, original) in ids.iter().zip(originals) {
+            let original = original.ok_or(josh_error(&format!(
+                "no original commit found for filtered commit {}",
+                id
+            )))?;
+            revisions.push(Revision {
+                filter: self.filter,
+                commit_id: original,
+            });
+        }
+
+        Ok(revisions)
+    }
+
+    /// Number of commits reachable from the filtered commit, so commits of the filtered
+    /// history, not of the original one. With `limit` counting stops there.
+    fn commit_count(&self, limit: Option<i32>, context: &Context) -> FieldResult<i32> {
+        let limit = limit.map(|l| std::cmp::max(l, 0)).unwrap_or(i32::MAX);
+
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+        if filtered == git2::Oid::zero() {
+            return Ok(0);
+        }
+
+        let mut walk = transaction.repo().revwalk()?;
+        walk.push(filtered)?;
+        let mut count = 0;
+        for id in walk {
+            if count >= limit {
+                break;
+            }
+            id?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Number of entries of the directory `at` in the filtered tree, of any kind.
+    /// With `recursive` the entries of all subdirectories are included.
+    fn entry_count(
         &self,
         at: Option<String>,
-        depth: Option<i32>,
+        recursive: Option<bool>,
         context: &Context,
-    ) -> FieldResult<Option<Vec<Path>>> {
+    ) -> FieldResult<i32> {
         let transaction = context.transaction.lock()?;
         let commit = transaction.repo().find_commit(self.commit_id)?;
         let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
-        let tree_id = tree.id();
-
-        let paths = find_paths(&transaction, tree, at, depth, git2::ObjectType::Blob)?;
+        let tree = match at.as_ref().map(|at| at.as_str()) {
+            None | Some("") => tree,
+            Some(at) => tree
+                .get_path(std::path::Path::new(at))
+                .and_then(|entry| transaction.repo().find_tree(entry.id()))
+                .map_err(|_| josh_error(&format!("not a tree: {:?}", at)))?,
+        };
 
-        let mut ws = vec![];
-        for p in paths {
-            ws.push(Path {
-                path: p,
-                commit_id: self.commit_id,
-                filter: self.filter,
-                tree: tree_id,
-            });
+        if !recursive.unwrap_or(false) {
+            return Ok(tree.len() as i32);
         }
-        return Ok(Some(ws));
+        let mut count = 0;
+        tree.walk(git2::TreeWalkMode::PreOrder, |_, _| {
+            count += 1;
+            0
+        })?;
+        Ok(count)
+    }
+
+    /// Files below `at`. If `at` is not a directory this is an error,
+    /// unless `strict` is set to `false`, then the result is empty.
+    /// With `glob` only paths matching it relative to `at` are returned.
+    fn files(
+        &self,
+        at: Option<String>,
+        depth: Option<i32>,
+        sort: Option<bool>,
+        strict: Option<bool>,
+        glob: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Option<Vec<Path>>> {
+        let query = PathQuery {
+            kind: git2::ObjectType::Blob,
+            at,
+            depth,
+            sort: sort.unwrap_or(false),
+            strict,
+            glob,
+        };
+        Ok(Some(self.paths(context, query)?))
     }
 
+    /// Page of the sorted files, the other arguments work like for `files`.
+    /// At most `first` paths following the path `after` are returned, pass the
+    /// last path of a page as `after` to get the next one.
+    fn files_page(
+        &self,
+        at: Option<String>,
+        depth: Option<i32>,
+        strict: Option<bool>,
+        glob: Option<String>,
+        first: Option<i32>,
+        after: Option<String>,
+        context: &Context,
+    ) -> FieldResult<PathPage> {
+        let query = PathQuery {
+            kind: git2::ObjectType::Blob,
+            at,
+            depth,
+            sort: true,
+            strict,
+            glob,
+        };
+        Ok(self.path_page(context, query, first, after)?)
+    }
+
+    /// Directories below `at`, `strict` and `glob` work like for `files`
     fn dirs(
         &self,
         at: Option<String>,
         depth: Option<i32>,
+        sort: Option<bool>,
+        strict: Option<bool>,
+        glob: Option<String>,
         context: &Context,
     ) -> FieldResult<Option<Vec<Path>>> {
+        let query = PathQuery {
+            kind: git2::ObjectType::Tree,
+            at,
+            depth,
+            sort: sort.unwrap_or(false),
+            strict,
+            glob,
+        };
+        Ok(Some(self.paths(context, query)?))
+    }
+
+    /// Page of the sorted directories, the arguments work like for `filesPage`
+    fn dirs_page(
+        &self,
+        at: Option<String>,
+        depth: Option<i32>,
+        strict: Option<bool>,
+        glob: Option<String>,
+        first: Option<i32>,
+        after: Option<String>,
+        context: &Context,
+    ) -> FieldResult<PathPage> {
+        let query = PathQuery {
+            kind: git2::ObjectType::Tree,
+            at,
+            depth,
+            sort: true,
+            strict,
+            glob,
+        };
+        Ok(self.path_page(context, query, first, after)?)
+    }
+
+    /// Sorted paths of all files and directories in the filtered tree matching `pattern`
+    fn glob(&self, pattern: String, context: &Context) -> FieldResult<Vec<String>> {
+        let glob = glob::Pattern::new(&pattern)
+            .map_err(|e| josh_error(&format!("invalid glob {:?}: {}", pattern, e.msg)))?;
+
         let transaction = context.transaction.lock()?;
-        let commit = transaction.repo().find_commit(self.commit_id)?;
-        let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
-        let tree_id = tree.id();
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        let tree = filter::apply(&transaction, self.filter, tree)?;
 
-        let paths = find_paths(&transaction, tree, at, depth, git2::ObjectType::Tree)?;
+        let mut paths = vec![];
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            match (entry.kind(), entry.name()) {
+                (Some(git2::ObjectType::Blob), Some(name))
+                | (Some(git2::ObjectType::Tree), Some(name)) => {
+                    let path = std::path::Path::new(root).join(name);
+                    if glob.matches_path_with(&path, GLOB_OPTIONS) {
+                        paths.push(path);
+                    }
+                }
+                _ => {}
+            }
+            0
+        })?;
+        paths.sort();
+
+        Ok(paths
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
 
-        let mut ws = vec![];
-        for p in paths {
-            ws.push(Path {
-                path: p,
-                commit_id: self.commit_id,
-                filter: self.filter,
-                tree: tree_id,
+    /// Contents of several files, filtering the tree only once. Paths that
+    /// can't be read have `error` set instead of failing the whole request.
+    fn texts(&self, paths: Vec<String>, context: &Context) -> FieldResult<Vec<FileContent>> {
+        let transaction = context.transaction.lock()?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+
+        let tree = filter::apply(&transaction, self.filter, tree)?;
+
+        let mut contents = vec![];
+        for path in paths {
+            let blob = tree
+                .get_path(std::path::Path::new(&path))
+                .map_err(|e| e.message().to_string())
+                .and_then(|entry| {
+                    transaction
+                        .repo()
+                        .find_blob(entry.id())
+                        .map_err(|_| format!("not a blob: {:?}", path))
+                });
+            contents.push(match blob {
+                Ok(blob) => {
+                    let text = std::str::from_utf8(blob.content())
+                        .ok()
+                        .map(|x| x.to_string());
+                    FileContent {
+                        path,
+                        is_binary: text.is_none(),
+                        text,
+                        error: None,
+                    }
+                }
+                Err(error) => FileContent {
+                    path,
+                    text: None,
+                    is_binary: false,
+                    error: Some(error),
+                },
             });
         }
-        return Ok(Some(ws));
+        Ok(contents)
+    }
+
+    /// Base64 encoded archive of the filtered tree (or the subdirectory
+    /// given by `at`). Supported formats are "tar" and "zip".
+    fn archive(
+        &self,
+        at: Option<String>,
+        format: String,
+        context: &Context,
+    ) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let mut tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+
+        if let Some(at) = at {
+            if at != "" {
+                let error = |msg: &str| {
+                    path_error(msg, std::path::Path::new(&at), self.filter, self.commit_id)
+                };
+                let entry = tree
+                    .get_path(&std::path::Path::new(&at))
+                    .map_err(|e| error(e.message()))?;
+                tree = transaction
+                    .repo()
+                    .find_tree(entry.id())
+                    .map_err(|_| error(&format!("not a tree: {:?}", at)))?;
+            }
+        }
+
+        let mtime = commit.time().seconds();
+        let data = match format.as_str() {
+            "tar" => archive::tar(transaction.repo(), &tree, mtime)?,
+            "zip" => archive::zip(transaction.repo(), &tree, mtime)?,
+            _ => Err(josh_error(&format!(
+                "unsupported archive format: {:?}",
+                format
+            )))?,
+        };
+
+        Ok(base64::encode(data))
     }
 
     fn file(&self, path: String, context: &Context) -> FieldResult<Option<Path>> {
@@ -194,7 +1094,10 @@ impl Revision {
 
         let tree = filter::apply(&transaction, self.filter, tree)?;
 
-        if let Some(git2::ObjectType::Blob) = tree.get_path(&path)?.kind() {
+        let error = |msg: &str| path_error(msg, &path, self.filter, self.commit_id);
+        let entry = tree.get_path(&path).map_err(|e| error(e.message()))?;
+
+        if let Some(git2::ObjectType::Blob) = entry.kind() {
             Ok(Some(Path {
                 path: path,
                 commit_id: self.commit_id,
@@ -202,10 +1105,78 @@ impl Revision {
                 tree: tree.id(),
             }))
         } else {
-            Err(josh_error("not a blob"))?
+            Err(error("not a blob"))
         }
     }
 
+    /// Whether the files `a` and `b` of the filtered tree have the same content
+    fn same_content(&self, a: String, b: String, context: &Context) -> FieldResult<bool> {
+        let transaction = context.transaction.lock()?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        let tree = filter::apply(&transaction, self.filter, tree)?;
+
+        let blob = |path: &str| {
+            let path = std::path::Path::new(path);
+            let error = |msg: &str| path_error(msg, &path, self.filter, self.commit_id);
+            let entry = tree.get_path(&path).map_err(|e| error(e.message()))?;
+            if let Some(git2::ObjectType::Blob) = entry.kind() {
+                Ok(entry.id())
+            } else {
+                Err(error("not a blob"))
+            }
+        };
+
+        Ok(blob(&a)? == blob(&b)?)
+    }
+
+    /// Package manifest in the root of the filtered tree: `Cargo.toml` for `kind: "cargo"`,
+    /// `package.json` for `"npm"` and `pyproject.toml` for `"python"`
+    fn manifest(&self, kind: String, context: &Context) -> FieldResult<Document> {
+        let (name, is_toml) = match kind.as_str() {
+            "cargo" => ("Cargo.toml", true),
+            "npm" => ("package.json", false),
+            "python" => ("pyproject.toml", true),
+            _ => return Err(josh_error(&format!("unknown manifest kind {:?}", kind)))?,
+        };
+
+        let transaction = context.transaction.lock()?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        let tree = filter::apply(&transaction, self.filter, tree)?;
+        let path = Path {
+            path: std::path::PathBuf::from(name),
+            commit_id: self.commit_id,
+            filter: self.filter,
+            tree: tree.id(),
+        };
+
+        let blob = path.blob(&transaction, context)?;
+        let input = path.parse_input(&blob, context)?;
+        let value = if is_toml {
+            toml::de::from_str::<serde_json::Value>(input).ok()
+        } else {
+            serde_json::from_str::<serde_json::Value>(input).ok()
+        };
+
+        Ok(Document {
+            id: blob.id(),
+            value: value.unwrap_or(json!({})),
+        })
+    }
+
+    /// Root of the filtered tree, the same as `dir` without a path
+    fn tree(&self, context: &Context) -> FieldResult<Path> {
+        let transaction = context.transaction.lock()?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        let tree = filter::apply(&transaction, self.filter, tree)?;
+
+        Ok(Path {
+            path: std::path::PathBuf::new(),
+            commit_id: self.commit_id,
+            filter: self.filter,
+            tree: tree.id(),
+        })
+    }
+
     fn dir(&self, path: Option<String>, context: &Context) -> FieldResult<Option<Path>> {
         let path = path.unwrap_or_default();
         let transaction = context.transaction.lock()?;
@@ -224,7 +1195,10 @@ impl Revision {
             }));
         }
 
-        if let Some(git2::ObjectType::Tree) = tree.get_path(&path)?.kind() {
+        let error = |msg: &str| path_error(msg, &path, self.filter, self.commit_id);
+        let entry = tree.get_path(&path).map_err(|e| error(e.message()))?;
+
+        if let Some(git2::ObjectType::Tree) = entry.kind() {
             Ok(Some(Path {
                 path: path,
                 commit_id: self.commit_id,
@@ -232,7 +1206,7 @@ impl Revision {
                 tree: tree.id(),
             }))
         } else {
-            Err(josh_error("not a tree"))?
+            Err(error("not a tree"))
         }
     }
 
@@ -249,6 +1223,45 @@ impl Revision {
 
         Ok(Some(warnings))
     }
+
+    /// Same as `warnings`, but the checks stop after `timeoutMs` milliseconds
+    /// (default 1000) to keep the query responsive on large trees
+    fn bounded_warnings(
+        &self,
+        timeout_ms: Option<i32>,
+        context: &Context,
+    ) -> FieldResult<Warnings> {
+        let deadline = std::time::Instant::now()
+            + std::time::Duration::from_millis(std::cmp::max(timeout_ms.unwrap_or(1000), 0) as u64);
+        let transaction = context.transaction.lock()?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+
+        let (warnings, truncated) = filter::compute_warnings_until(
+            &transaction,
+            self.filter,
+            commit.tree()?,
+            Some(deadline),
+        );
+
+        Ok(Warnings {
+            warnings: warnings
+                .iter()
+                .map(|warn| Warning {
+                    text: warn.to_string(),
+                })
+                .collect(),
+            truncated: truncated,
+        })
+    }
+}
+
+#[derive(juniper::GraphQLObject)]
+pub struct FileContent {
+    path: String,
+    text: Option<String>,
+    is_binary: bool,
+    /// Why the file could not be read, null on success
+    error: Option<String>,
 }
 
 pub struct Warning {
@@ -262,6 +1275,40 @@ impl Warning {
     }
 }
 
+pub struct Warnings {
+    warnings: Vec<Warning>,
+    truncated: bool,
+}
+
+#[graphql_object(context = Context)]
+impl Warnings {
+    fn warnings(&self) -> &Vec<Warning> {
+        &self.warnings
+    }
+
+    /// `true` if not all checks were done in time, so there can be more warnings
+    fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+pub struct BranchWarnings {
+    branch: String,
+    warnings: Vec<Warning>,
+}
+
+#[graphql_object(context = Context)]
+impl BranchWarnings {
+    /// Name of the branch, like "refs/heads/master"
+    fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    fn warnings(&self) -> &Vec<Warning> {
+        &self.warnings
+    }
+}
+
 pub struct Path {
     path: std::path::PathBuf,
     commit_id: git2::Oid,
@@ -269,6 +1316,88 @@ pub struct Path {
     tree: git2::Oid,
 }
 
+/// Error with the `path`, `filter` and `commit` it refers to attached as extensions,
+/// so clients don't need to parse the message
+fn path_error(
+    message: &str,
+    path: &std::path::Path,
+    filter: filter::Filter,
+    commit_id: git2::Oid,
+) -> juniper::FieldError {
+    let mut extensions = juniper::Object::with_capacity(3);
+    extensions.add_field(
+        "path",
+        juniper::Value::scalar(path.to_string_lossy().to_string()),
+    );
+    extensions.add_field("filter", juniper::Value::scalar(filter::spec(filter)));
+    extensions.add_field("commit", juniper::Value::scalar(commit_id.to_string()));
+    juniper::FieldError::new(message, juniper::Value::Object(extensions))
+}
+
+/// Maximum number of commits walked by `Path.lastModified`
+const LAST_MODIFIED_LIMIT: usize = 10000;
+
+impl Path {
+    fn error(&self, message: &str) -> juniper::FieldError {
+        path_error(message, &self.path, self.filter, self.commit_id)
+    }
+
+    /// Id of the object at `path`, looked up in the entry cache of `context` first
+    fn id(&self, transaction: &cache::Transaction, context: &Context) -> FieldResult<git2::Oid> {
+        let key = (self.tree, self.path.clone());
+        if let Some(entries) = &context.entries {
+            if let Some(id) = entries.lock()?.get(&key) {
+                return Ok(*id);
+            }
+        }
+        let id = transaction
+            .repo()
+            .find_tree(self.tree)?
+            .get_path(&self.path)
+            .map_err(|e| self.error(e.message()))?
+            .id();
+        if let Some(entries) = &context.entries {
+            entries.lock()?.insert(key, id);
+        }
+        Ok(id)
+    }
+
+    fn blob<'a>(
+        &self,
+        transaction: &'a cache::Transaction,
+        context: &Context,
+    ) -> FieldResult<git2::Blob<'a>> {
+        let id = self.id(transaction, context)?;
+        transaction
+            .repo()
+            .find_blob(id)
+            .map_err(|_| self.error("not a blob"))
+    }
+
+    /// Content of `blob` for parsing, if it is within the size limit
+    fn parse_input<'a>(&self, blob: &'a git2::Blob, context: &Context) -> FieldResult<&'a str> {
+        if blob.size() > context.max_parse_size {
+            return Err(self.error(&format!(
+                "{} is too large to parse ({} bytes, limit is {})",
+                self.path.to_string_lossy(),
+                blob.size(),
+                context.max_parse_size
+            )));
+        }
+        self.utf8(blob)
+    }
+
+    fn utf8<'a>(&self, blob: &'a git2::Blob) -> FieldResult<&'a str> {
+        std::str::from_utf8(blob.content()).map_err(|e| {
+            self.error(&format!(
+                "invalid UTF-8 in {} at byte {}",
+                self.path.to_string_lossy(),
+                e.valid_up_to()
+            ))
+        })
+    }
+}
+
 pub fn linecount(repo: &git2::Repository, id: git2::Oid) -> usize {
     if let Ok(blob) = repo.find_blob(id) {
         return blob.content().iter().filter(|x| **x == '\n' as u8).count()
@@ -346,12 +1475,26 @@ impl Markers {
         Ok(lines)
     }
 
+    /// Number of markers on the path and below. Counts are cached until
+    /// `refs/josh/meta` changes.
     fn count(&self, context: &Context) -> FieldResult<i32> {
         let transaction = context.transaction.lock()?;
 
         let refname = transaction.refname("refs/josh/meta");
 
         let r = transaction.repo().revparse_single(&refname);
+        let meta_commit = r.as_ref().map(|r| r.id()).unwrap_or(git2::Oid::zero());
+        let key = (
+            meta_commit,
+            self.commit_id,
+            self.filter,
+            self.path.clone(),
+            self.topic.clone(),
+        );
+        if let Some(count) = transaction.get_marker_count(&key) {
+            return Ok(count as i32);
+        }
+
         let mtree = if let Ok(r) = r {
             let commit = transaction.repo().find_commit(r.id())?;
             commit.tree()?
@@ -379,12 +1522,15 @@ impl Markers {
                     mtree,
                 )?)?
         };
-        if let Ok(p) = mtree.get_path(&self.path) {
-            return Ok(linecount(transaction.repo(), p.id()) as i32);
+        let count = if let Ok(p) = mtree.get_path(&self.path) {
+            linecount(transaction.repo(), p.id())
         } else if self.path == std::path::Path::new("") {
-            return Ok(linecount(transaction.repo(), mtree.id()) as i32);
-        }
-        return Ok(0);
+            linecount(transaction.repo(), mtree.id())
+        } else {
+            0
+        };
+        transaction.insert_marker_count(key, count);
+        return Ok(count as i32);
     }
 }
 
@@ -412,89 +1558,432 @@ impl Path {
         }
     }
 
-    fn rev(&self, filter: String) -> FieldResult<Revision> {
+    fn rev(&self, filter: String, context: &Context) -> FieldResult<Revision> {
         let hm: std::collections::HashMap<String, String> =
             [("path".to_string(), self.path.to_string_lossy().to_string())]
                 .iter()
                 .cloned()
                 .collect();
         Ok(Revision {
-            filter: filter::parse(&strfmt::strfmt(&filter, &hm)?)?,
+            filter: context.parse_filter(&strfmt::strfmt(&filter, &hm)?)?,
             commit_id: self.commit_id,
         })
     }
 
+    /// The most recent commit that changed this path, following first parents.
+    /// Every step of the walk filters one tree, so the walk is limited to
+    /// `LAST_MODIFIED_LIMIT` commits, beyond that an error is returned.
+    fn last_modified(&self, context: &Context) -> FieldResult<Revision> {
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+        let id_at = |commit: &git2::Commit| -> JoshResult<Option<git2::Oid>> {
+            let tree = filter::apply(&transaction, self.filter, commit.tree()?)?;
+            Ok(tree.get_path(&self.path).ok().map(|entry| entry.id()))
+        };
+
+        let mut commit = repo.find_commit(self.commit_id)?;
+        let id = id_at(&commit)?;
+        if id.is_none() {
+            return Err(self.error("path does not exist"));
+        }
+        for _ in 0..LAST_MODIFIED_LIMIT {
+            let parent = some_or!(commit.parents().next(), {
+                return Ok(Revision {
+                    filter: self.filter,
+                    commit_id: commit.id(),
+                });
+            });
+            if id_at(&parent)? != id {
+                return Ok(Revision {
+                    filter: self.filter,
+                    commit_id: commit.id(),
+                });
+            }
+            commit = parent;
+        }
+        Err(self.error(&format!(
+            "no change found within {} commits",
+            LAST_MODIFIED_LIMIT
+        )))
+    }
+
+    /// Git attributes that apply to this file in the unfiltered tree, so they
+    /// can be written to a `.gitattributes` file when exporting the filtered tree
+    fn attributes(&self, context: &Context) -> FieldResult<Vec<String>> {
+        let transaction = context.transaction.lock()?;
+        self.blob(&transaction, context)?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        Ok(filter::tree::attributes(
+            &transaction,
+            self.filter,
+            tree,
+            &self.path,
+        )?)
+    }
+
+    /// For each filter in `specs` whether this file is part of its output
+    /// when applied to the original tree of the commit
+    fn survives_filters(
+        &self,
+        specs: Vec<String>,
+        context: &Context,
+    ) -> FieldResult<Vec<FilterSurvival>> {
+        let transaction = context.transaction.lock()?;
+        self.blob(&transaction, context)?;
+        let tree = transaction.repo().find_commit(self.commit_id)?.tree()?;
+        let original = if self.filter == filter::nop() {
+            self.path.to_string_lossy().to_string()
+        } else {
+            filter::tree::original_path(&transaction, self.filter, tree.clone(), &self.path)?
+        };
+
+        let mut result = vec![];
+        for spec in specs {
+            let filter = context.parse_filter(&spec)?;
+            let path = filter::tree::filtered_path(
+                &transaction,
+                filter,
+                tree.clone(),
+                std::path::Path::new(&original),
+            )?;
+            result.push(FilterSurvival {
+                spec: spec,
+                survives: path.is_some(),
+                path: path,
+            });
+        }
+        Ok(result)
+    }
+
     fn hash(&self, context: &Context) -> FieldResult<String> {
         let transaction = context.transaction.lock()?;
-        let id = transaction
-            .repo()
-            .find_tree(self.tree)?
-            .get_path(&self.path)?
-            .id();
+        let id = self.id(&transaction, context)?;
         Ok(format!("{}", id))
     }
+    /// Content of the file, `null` if it is not valid UTF-8
     fn text(&self, context: &Context) -> FieldResult<Option<String>> {
         let transaction = context.transaction.lock()?;
-        let id = transaction
+        let blob = self.blob(&transaction, context)?;
+
+        Ok(std::str::from_utf8(blob.content())
+            .ok()
+            .map(|text| text.to_string()))
+    }
+
+    /// Content of the file with invalid UTF-8 sequences replaced by `U+FFFD`
+    fn text_lossy(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+
+        Ok(String::from_utf8_lossy(blob.content()).to_string())
+    }
+
+    /// Git file mode of the entry in octal, like `100644`, `100755`, `120000` for
+    /// symlinks, `160000` for submodules and `040000` for directories
+    fn mode(&self, context: &Context) -> FieldResult<String> {
+        if self.path == std::path::Path::new("") {
+            return Ok(format!("{:06o}", 0o040000));
+        }
+        let transaction = context.transaction.lock()?;
+        let tree = transaction.repo().find_tree(self.tree)?;
+        let entry = tree
+            .get_path(&self.path)
+            .map_err(|e| self.error(e.message()))?;
+        Ok(format!("{:06o}", entry.filemode()))
+    }
+
+    /// Names and object ids of the immediate children of a directory, sorted by name
+    fn entry_hashes(&self, context: &Context) -> FieldResult<Vec<EntryHash>> {
+        let transaction = context.transaction.lock()?;
+        let id = if self.path == std::path::Path::new("") {
+            self.tree
+        } else {
+            self.id(&transaction, context)?
+        };
+        let tree = transaction
             .repo()
-            .find_tree(self.tree)?
-            .get_path(&self.path)?
-            .id();
-        let blob = transaction.repo().find_blob(id)?;
+            .find_tree(id)
+            .map_err(|_| self.error("not a tree"))?;
 
-        Ok(Some(std::str::from_utf8(blob.content())?.to_string()))
+        Ok(tree
+            .iter()
+            .map(|entry| EntryHash {
+                name: String::from_utf8_lossy(entry.name_bytes()).to_string(),
+                oid: entry.id().to_string(),
+                kind: entry
+                    .kind()
+                    .map(|kind| kind.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect())
     }
 
-    fn toml(&self, context: &Context) -> FieldResult<Document> {
+    /// Size of the file in bytes, an error for directories
+    fn size(&self, context: &Context) -> FieldResult<f64> {
         let transaction = context.transaction.lock()?;
-        let id = transaction
+        let blob = self.blob(&transaction, context)?;
+        Ok(blob.size() as f64)
+    }
+
+    /// For every line of the file the filtered commit that last changed it. This blames
+    /// the filtered history, so the hashes are the ones `Revision.hash` returns.
+    fn blame(&self, context: &Context) -> FieldResult<Vec<BlameLine>> {
+        let transaction = context.transaction.lock()?;
+        self.blob(&transaction, context)?;
+        let commit = transaction.repo().find_commit(self.commit_id)?;
+        let filtered = filter::apply_to_commit(self.filter, &commit, &transaction)?;
+
+        let mut options = git2::BlameOptions::new();
+        options.newest_commit(filtered);
+        let blame = transaction
             .repo()
-            .find_tree(self.tree)?
-            .get_path(&self.path)?
-            .id();
-        let blob = transaction.repo().find_blob(id)?;
-        let value = toml::de::from_str::<serde_json::Value>(std::str::from_utf8(blob.content())?)
+            .blame_file(&self.path, Some(&mut options))
+            .map_err(|e| self.error(e.message()))?;
+
+        let mut lines = vec![];
+        for hunk in blame.iter() {
+            let signature = hunk.final_signature();
+            for i in 0..hunk.lines_in_hunk() {
+                lines.push(BlameLine {
+                    line: (hunk.final_start_line() + i) as i32,
+                    commit: hunk.final_commit_id().to_string(),
+                    author_name: signature.name().unwrap_or("").to_owned(),
+                    author_email: signature.email().unwrap_or("").to_owned(),
+                });
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Content of the file encoded as base64, for binary files
+    fn base64(&self, context: &Context) -> FieldResult<String> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+
+        Ok(base64::encode(blob.content()))
+    }
+
+    /// Content of the file as a list of byte values, for files of at most
+    /// `Context::set_max_raw_bytes_size` bytes
+    fn raw_bytes(&self, context: &Context) -> FieldResult<Vec<i32>> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+        if blob.size() > context.max_raw_bytes_size {
+            return Err(self.error(&format!(
+                "{} is too large for rawBytes ({} bytes, limit is {})",
+                self.path.to_string_lossy(),
+                blob.size(),
+                context.max_raw_bytes_size
+            )));
+        }
+
+        Ok(blob.content().iter().map(|b| *b as i32).collect())
+    }
+
+    fn toml(&self, context: &Context) -> FieldResult<Document> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+        let value = toml::de::from_str::<serde_json::Value>(self.parse_input(&blob, context)?)
             .unwrap_or(json!({}));
 
         Ok(Document {
-            id: id,
+            id: blob.id(),
             value: value,
         })
     }
 
     fn json(&self, context: &Context) -> FieldResult<Document> {
         let transaction = context.transaction.lock()?;
-        let id = transaction
-            .repo()
-            .find_tree(self.tree)?
-            .get_path(&self.path)?
-            .id();
-        let blob = transaction.repo().find_blob(id)?;
-        let value = serde_json::from_str::<serde_json::Value>(std::str::from_utf8(blob.content())?)
+        let blob = self.blob(&transaction, context)?;
+        let value = serde_json::from_str::<serde_json::Value>(self.parse_input(&blob, context)?)
             .unwrap_or(json!({}));
 
         Ok(Document {
-            id: id,
+            id: blob.id(),
             value: value,
         })
     }
 
     fn yaml(&self, context: &Context) -> FieldResult<Document> {
         let transaction = context.transaction.lock()?;
-        let id = transaction
-            .repo()
-            .find_tree(self.tree)?
-            .get_path(&self.path)?
-            .id();
-        let blob = transaction.repo().find_blob(id)?;
-        let value = serde_yaml::from_str::<serde_json::Value>(std::str::from_utf8(blob.content())?)
+        let blob = self.blob(&transaction, context)?;
+        let value = serde_yaml::from_str::<serde_json::Value>(self.parse_input(&blob, context)?)
             .unwrap_or(json!({}));
 
         Ok(Document {
-            id: id,
+            id: blob.id(),
             value: value,
         })
     }
+
+    /// XML converted to JSON: attributes become `@name` keys, child elements keys by
+    /// name, lists if a name repeats, and the text `#text`. Elements with only text
+    /// become strings. The root element is the only key of the result.
+    fn xml(&self, context: &Context) -> FieldResult<Document> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+        let value = parse_xml(self.parse_input(&blob, context)?).map_err(|e| {
+            self.error(&format!(
+                "invalid XML in {}: {}",
+                self.path.to_string_lossy(),
+                e
+            ))
+        })?;
+
+        Ok(Document {
+            id: blob.id(),
+            value: value,
+        })
+    }
+
+    /// Rows of a CSV file as a list of objects keyed by the header row.
+    /// All values are strings, a file without rows gives an empty list.
+    fn csv(&self, context: &Context) -> FieldResult<Document> {
+        let transaction = context.transaction.lock()?;
+        let blob = self.blob(&transaction, context)?;
+        let error = |e: csv::Error| {
+            self.error(&format!(
+                "invalid CSV in {}: {}",
+                self.path.to_string_lossy(),
+                e
+            ))
+        };
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_reader(self.parse_input(&blob, context)?.as_bytes());
+        let header = reader.headers().map_err(error)?.clone();
+
+        let mut value = vec![];
+        for record in reader.records() {
+            let object: serde_json::Map<_, _> = header
+                .iter()
+                .zip(record.map_err(error)?.iter())
+                .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string())))
+                .collect();
+            value.push(serde_json::Value::Object(object));
+        }
+
+        Ok(Document {
+            id: blob.id(),
+            value: serde_json::Value::Array(value),
+        })
+    }
+}
+
+const MAX_XML_DEPTH: usize = 256;
+
+/// Convert XML to JSON. An element becomes an object with its attributes as `@name`
+/// keys, its child elements by name (a list if the name repeats) and its text as
+/// `#text`. Elements with only text become a string. The result is an object with
+/// the root element as the only key. Element and attribute names are kept as written,
+/// including namespace prefixes.
+///
+/// Elements can't be nested deeper than `MAX_XML_DEPTH`, the resulting JSON is
+/// dropped and serialized recursively.
+fn parse_xml(input: &str) -> Result<serde_json::Value, String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(input);
+    let error = |message: &dyn std::fmt::Display, pos: u64| format!("{} at byte {}", message, pos);
+
+    // The open elements with their name, attributes and children, and text
+    let mut stack: Vec<(String, serde_json::Map<String, serde_json::Value>, String)> = vec![];
+    let mut root = serde_json::Map::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| error(&e, reader.error_position()))?;
+        let pos = reader.buffer_position();
+        let close = match &event {
+            Event::Start(start) | Event::Empty(start) => {
+                if !root.is_empty() {
+                    return Err(error(&"content after the root element", pos));
+                }
+                if stack.len() >= MAX_XML_DEPTH {
+                    return Err(error(
+                        &format!("elements nested deeper than {}", MAX_XML_DEPTH),
+                        pos,
+                    ));
+                }
+                let mut object = serde_json::Map::new();
+                for attribute in start.attributes() {
+                    let attribute = attribute.map_err(|e| error(&e, pos))?;
+                    let value = attribute.unescape_value().map_err(|e| error(&e, pos))?;
+                    object.insert(
+                        format!("@{}", String::from_utf8_lossy(attribute.key.as_ref())),
+                        serde_json::Value::String(value.into_owned()),
+                    );
+                }
+                let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                stack.push((name, object, String::new()));
+                matches!(event, Event::Empty(_))
+            }
+            Event::End(_) => true,
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| error(&e, pos))?;
+                match stack.last_mut() {
+                    Some((_, _, content)) => content.push_str(&text),
+                    None if text.trim().is_empty() => {}
+                    None => return Err(error(&"text outside of the root element", pos)),
+                }
+                false
+            }
+            Event::CData(text) => {
+                match stack.last_mut() {
+                    Some((_, _, content)) => content.push_str(&String::from_utf8_lossy(text)),
+                    None => return Err(error(&"text outside of the root element", pos)),
+                }
+                false
+            }
+            Event::Eof => {
+                if let Some((name, _, _)) = stack.last() {
+                    return Err(error(&format!("unclosed <{}>", name), pos));
+                }
+                if root.is_empty() {
+                    return Err(error(&"missing root element", pos));
+                }
+                return Ok(serde_json::Value::Object(root));
+            }
+            Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => false,
+        };
+
+        if close {
+            let (child, object, text) = stack
+                .pop()
+                .ok_or_else(|| error(&"unexpected end tag", pos))?;
+            let value = xml_value(object, &text);
+            let object = match stack.last_mut() {
+                Some((_, object, _)) => object,
+                None => &mut root,
+            };
+            // Element values are never lists, so a list means the name repeats
+            match object.get_mut(&child) {
+                Some(serde_json::Value::Array(values)) => values.push(value),
+                Some(existing) => *existing = json!([existing.take(), value]),
+                None => {
+                    object.insert(child, value);
+                }
+            }
+        }
+    }
+}
+
+fn xml_value(
+    mut object: serde_json::Map<String, serde_json::Value>,
+    text: &str,
+) -> serde_json::Value {
+    let text = text.trim();
+    if object.is_empty() {
+        return serde_json::Value::String(text.to_string());
+    }
+    if !text.is_empty() {
+        object.insert(
+            "#text".to_string(),
+            serde_json::Value::String(text.to_string()),
+        );
+    }
+    serde_json::Value::Object(object)
 }
 
 pub struct Document {
@@ -516,6 +2005,104 @@ impl Document {
     }
 }
 
+/// Run the jq `program` with `input` as `.` and return all outputs. Errors of
+/// loading and compiling the program include its position in `program`.
+fn run_jq(program: &str, input: &serde_json::Value) -> JoshResult<Vec<serde_json::Value>> {
+    use jaq_core::load::{self, Arena, File, Loader};
+
+    let error = |message: String| josh_error(&format!("jq: {}", message));
+    let position = |s: &str| s.as_ptr() as usize - program.as_ptr() as usize;
+    let expected =
+        |expect: &str, at: &str| format!("expected {} at position {}", expect, position(at));
+
+    let arena = Arena::default();
+    let modules = Loader::new(jaq_std::defs().chain(jaq_json::defs()))
+        .load(
+            &arena,
+            File {
+                code: program,
+                path: (),
+            },
+        )
+        .map_err(|errors| {
+            let messages: Vec<_> = errors
+                .into_iter()
+                .flat_map(|(_, e)| match e {
+                    load::Error::Io(e) => e.into_iter().map(|(_, m)| m).collect::<Vec<_>>(),
+                    load::Error::Lex(e) => e
+                        .into_iter()
+                        .map(|(expect, at)| expected(expect.as_str(), at))
+                        .collect(),
+                    load::Error::Parse(e) => e
+                        .into_iter()
+                        .map(|(expect, at)| expected(expect.as_str(), at))
+                        .collect(),
+                })
+                .collect();
+            error(messages.join(", "))
+        })?;
+    let filter = jaq_core::Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let messages: Vec<_> = errors
+                .into_iter()
+                .flat_map(|(_, e)| e)
+                .map(|(name, undefined)| {
+                    format!(
+                        "undefined {} {:?} at position {}",
+                        undefined.as_str(),
+                        name,
+                        position(name)
+                    )
+                })
+                .collect();
+            error(messages.join(", "))
+        })?;
+
+    let inputs = jaq_core::RcIter::new(std::iter::empty());
+    filter
+        .run((
+            jaq_core::Ctx::new([], &inputs),
+            jaq_json::Val::from(input.clone()),
+        ))
+        .map(|x| {
+            x.map(serde_json::Value::from)
+                .map_err(|e| error(e.to_string()))
+        })
+        .collect()
+}
+
+#[derive(juniper::GraphQLObject)]
+pub struct FlatEntry {
+    path: String,
+    value: String,
+}
+
+fn flatten_value(pointer: &str, value: &serde_json::Value, entries: &mut Vec<FlatEntry>) {
+    let escape = |x: &str| x.replace("~", "~0").replace("/", "~1");
+    match value {
+        serde_json::Value::Object(o) => {
+            for (k, v) in o.iter() {
+                flatten_value(&format!("{}/{}", pointer, escape(k)), v, entries);
+            }
+        }
+        serde_json::Value::Array(a) => {
+            for (i, v) in a.iter().enumerate() {
+                flatten_value(&format!("{}/{}", pointer, i), v, entries);
+            }
+        }
+        serde_json::Value::String(s) => entries.push(FlatEntry {
+            path: pointer.to_string(),
+            value: s.clone(),
+        }),
+        v => entries.push(FlatEntry {
+            path: pointer.to_string(),
+            value: v.to_string(),
+        }),
+    }
+}
+
 #[graphql_object(context = Context)]
 impl Document {
     fn string(&self, at: Option<String>, default: Option<String>) -> Option<String> {
@@ -534,14 +2121,60 @@ impl Document {
         }
     }
 
+    /// Integers clamped to the 32 bit range of GraphQL `Int`, see `bigint` for larger numbers
     fn int(&self, at: Option<String>, default: Option<i32>) -> Option<i32> {
         if let serde_json::Value::Number(s) = &self.pointer(at) {
-            s.as_i64().map(|x| x as i32)
+            s.as_i64()
+                .map(|x| x.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+        } else {
+            default
+        }
+    }
+
+    /// Integers of the full 64 bit range, as decimal string
+    fn bigint(&self, at: Option<String>, default: Option<String>) -> Option<String> {
+        match &self.pointer(at) {
+            serde_json::Value::Number(s) if s.is_i64() || s.is_u64() => Some(s.to_string()),
+            _ => default,
+        }
+    }
+
+    /// Numbers as float, integers included
+    fn float(&self, at: Option<String>, default: Option<f64>) -> Option<f64> {
+        if let serde_json::Value::Number(s) = &self.pointer(at) {
+            s.as_f64().or(default)
         } else {
             default
         }
     }
 
+    /// Sorted keys of an object, `null` for other values and, like for `value`,
+    /// if nothing exists at the pointer `at`
+    fn keys(&self, at: Option<String>) -> Option<Vec<String>> {
+        let value = match &at {
+            Some(at) => self.value.pointer(at)?,
+            None => &self.value,
+        };
+        if let serde_json::Value::Object(o) = value {
+            let mut keys: Vec<_> = o.keys().cloned().collect();
+            keys.sort();
+            Some(keys)
+        } else {
+            None
+        }
+    }
+
+    /// Outputs of the jq `program` run on this document
+    fn jq(&self, program: String) -> FieldResult<Vec<Document>> {
+        Ok(run_jq(&program, &self.value)?
+            .into_iter()
+            .map(|value| Document {
+                id: git2::Oid::zero(),
+                value: value,
+            })
+            .collect())
+    }
+
     fn list(&self, at: Option<String>) -> Option<Vec<Document>> {
         let mut v = vec![];
         if let serde_json::Value::Array(a) = &self.pointer(at) {
@@ -567,6 +2200,29 @@ impl Document {
     fn id() -> String {
         self.id.to_string()
     }
+
+    /// All scalar leaves of the document with their JSON Pointer
+    fn flatten(&self) -> Vec<FlatEntry> {
+        let mut entries = vec![];
+        flatten_value("", &self.value, &mut entries);
+        entries
+    }
+
+    /// Apply a JSON Patch (RFC 6902) and return the resulting document
+    fn patch(&self, ops: String) -> FieldResult<Document> {
+        let ops = serde_json::from_str::<json_patch::Patch>(&ops)?;
+        let mut value = self.value.clone();
+        json_patch::patch(&mut value, &ops)
+            .map_err(|e| josh_error(&format!("json patch: {}", e)))?;
+        let id = git2::Oid::hash_object(
+            git2::ObjectType::Blob,
+            serde_json::to_string(&value)?.as_bytes(),
+        )?;
+        Ok(Document {
+            id: id,
+            value: value,
+        })
+    }
 }
 
 pub struct Reference {
@@ -590,7 +2246,7 @@ impl Reference {
             .unwrap_or(git2::Oid::zero());
 
         Ok(Revision {
-            filter: filter::parse(&filter.unwrap_or(":/".to_string()))?,
+            filter: context.parse_filter(&filter.unwrap_or(":/".to_string()))?,
             commit_id: id,
         })
     }
@@ -598,6 +2254,81 @@ impl Reference {
 
 pub struct Context {
     transaction: std::sync::Arc<std::sync::Mutex<cache::Transaction>>,
+    fallback_signature: (String, String),
+    filters: std::sync::Mutex<std::collections::HashMap<String, filter::Filter>>,
+    max_parse_size: usize,
+    max_raw_bytes_size: usize,
+    entries: Option<
+        std::sync::Mutex<std::collections::HashMap<(git2::Oid, std::path::PathBuf), git2::Oid>>,
+    >,
+    revs: std::sync::Mutex<std::collections::HashMap<String, git2::Oid>>,
+}
+
+impl Context {
+    /// Set the identity used for commits created by mutations in case the
+    /// repo does not have `user.name` and `user.email` configured.
+    pub fn set_fallback_signature(&mut self, name: &str, email: &str) {
+        self.fallback_signature = (name.to_string(), email.to_string());
+    }
+
+    /// Set the size limit in bytes for files parsed by `Path.toml`, `Path.json`
+    /// and `Path.yaml`. Larger files are rejected with an error.
+    pub fn set_max_parse_size(&mut self, max_parse_size: usize) {
+        self.max_parse_size = max_parse_size;
+    }
+
+    /// Set the size limit in bytes for files returned by `Path.rawBytes`
+    pub fn set_max_raw_bytes_size(&mut self, max_raw_bytes_size: usize) {
+        self.max_raw_bytes_size = max_raw_bytes_size;
+    }
+
+    /// Enable or disable caching the ids of the objects `Path` fields refer to.
+    /// The cache lives as long as the context and is enabled by default.
+    pub fn set_entry_cache(&mut self, enabled: bool) {
+        self.entries = if enabled {
+            Some(std::sync::Mutex::new(std::collections::HashMap::new()))
+        } else {
+            None
+        };
+    }
+
+    fn signature(&self, repo: &git2::Repository) -> JoshResult<git2::Signature<'static>> {
+        if let Ok(signature) = repo.signature() {
+            return Ok(signature);
+        }
+        Ok(git2::Signature::now(
+            &self.fallback_signature.0,
+            &self.fallback_signature.1,
+        )?)
+    }
+
+    /// Commit of the revision `rev` as it was the first time it was resolved within
+    /// this context. Refs can be updated by a fetch while a query runs; resolving
+    /// them here gives all fields of the query the same snapshot of them.
+    /// As commits are immutable, that is enough for consistent results.
+    fn resolve_rev(&self, transaction: &cache::Transaction, rev: &str) -> JoshResult<git2::Oid> {
+        if let Some(id) = self.revs.lock()?.get(rev) {
+            return Ok(*id);
+        }
+        let id = transaction
+            .repo()
+            .revparse_single(rev)?
+            .peel_to_commit()?
+            .id();
+        self.revs.lock()?.insert(rev.to_string(), id);
+        Ok(id)
+    }
+
+    /// Parse a filter spec, reusing the result of earlier parses of the same
+    /// spec within this context.
+    fn parse_filter(&self, spec: &str) -> JoshResult<filter::Filter> {
+        if let Some(f) = self.filters.lock()?.get(spec) {
+            return Ok(*f);
+        }
+        let f = filter::parse(spec)?;
+        self.filters.lock()?.insert(spec.to_string(), f);
+        Ok(f)
+    }
 }
 
 impl juniper::Context for Context {}
@@ -606,10 +2337,27 @@ pub struct Repository {
     name: String,
 }
 
-pub struct RepositoryMut {}
-
+pub struct RepositoryMut {}
+
+/// Make `topic` usable as a single path component: `/`, `\\`, `~` and `%` are
+/// percent-encoded, as are the dots of `.` and `..`. So every topic gets its own
+/// directory and can't reach the `~` subtrees of other topics or outside of them.
+fn escape_topic(topic: &str) -> String {
+    if topic != "" && topic.chars().all(|c| c == '.') {
+        return topic.replace(".", "%2E");
+    }
+    let mut escaped = String::new();
+    for c in topic.chars() {
+        match c {
+            '/' | '\\' | '~' | '%' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 fn marker_path(commit: &str, topic: &str) -> std::path::PathBuf {
-    std::path::Path::new(topic)
+    std::path::Path::new(&escape_topic(topic))
         .join("~")
         .join(&commit[..2])
         .join(&commit[2..5])
@@ -691,10 +2439,12 @@ impl RepositoryMut {
             tree = filter::tree::insert(transaction.repo(), &tree, &path, blob, 0o0100644)?;
         }
 
+        let signature = context.signature(transaction.repo())?;
+
         transaction.repo().commit(
             Some(&rev),
-            &transaction.repo().signature()?,
-            &transaction.repo().signature()?,
+            &signature,
+            &signature,
             "marker",
             &tree,
             &if let Some(parent) = parent.as_ref() {
@@ -706,6 +2456,114 @@ impl RepositoryMut {
 
         Ok(true)
     }
+
+    /// Attach `text` as git note to `commit` in the notes ref `ref`
+    /// (default "refs/notes/commits"), replacing an existing note
+    fn set_note(
+        &self,
+        commit: String,
+        r#ref: Option<String>,
+        text: String,
+        context: &Context,
+    ) -> FieldResult<bool> {
+        let transaction = context.transaction.lock()?;
+        let notes_ref = transaction.refname(&r#ref.unwrap_or("refs/notes/commits".to_string()));
+        let commit = git2::Oid::from_str(&commit)?;
+        transaction.repo().find_commit(commit)?;
+
+        let signature = context.signature(transaction.repo())?;
+        transaction.repo().note(
+            &signature,
+            &signature,
+            Some(&notes_ref),
+            commit,
+            &text,
+            true,
+        )?;
+
+        Ok(true)
+    }
+
+    /// Point `HEAD` of the repo at `branch`, given either as "master" or as
+    /// "refs/heads/master". The branch has to exist.
+    fn set_default_branch(&self, branch: String, context: &Context) -> FieldResult<bool> {
+        let transaction = context.transaction.lock()?;
+        let branch = if branch.starts_with("refs/heads/") {
+            branch
+        } else {
+            format!("refs/heads/{}", branch)
+        };
+        let target = transaction.refname(&branch);
+        if transaction.repo().find_reference(&target).is_err() {
+            return Err(josh_error(&format!("no branch {:?}", branch)))?;
+        }
+        transaction.repo().reference_symbolic(
+            &transaction.refname("HEAD"),
+            &target,
+            true,
+            "setDefaultBranch",
+        )?;
+        Ok(true)
+    }
+
+    /// Upstream commits, oldest first, that pushing the filtered commit `from` through
+    /// `filter` onto the upstream ref `onto` (default the branch `HEAD` points to) would
+    /// create. This is a mutation because the commits are written to the object
+    /// database, but no refs are changed.
+    fn preview_push(
+        &self,
+        filter: String,
+        from: String,
+        onto: Option<String>,
+        context: &Context,
+    ) -> FieldResult<Vec<String>> {
+        let filter = context.parse_filter(&filter)?;
+        let transaction = context.transaction.lock()?;
+        let onto = onto.unwrap_or("HEAD".to_string());
+        let original_target = resolve_in_namespace(context, &transaction, &onto)?;
+        let old = filter::apply_to_commit(
+            filter,
+            &transaction.repo().find_commit(original_target)?,
+            &transaction,
+        )?;
+        let new = resolve_in_namespace(context, &transaction, &from)?;
+
+        let rewritten = match history::unapply_filter(
+            &transaction,
+            filter,
+            original_target,
+            old,
+            new,
+            false,
+            None,
+            &std::collections::HashMap::new(),
+        )? {
+            UnapplyResult::Done(rewritten) => rewritten,
+            UnapplyResult::BranchDoesNotExist => {
+                return Err(josh_error("branch does not exist on remote"))?
+            }
+            UnapplyResult::RejectMerge(parent_count) => {
+                return Err(josh_error(&format!(
+                    "rejecting merge with {} parents",
+                    parent_count
+                )))?
+            }
+            UnapplyResult::RejectAmend(msg) => {
+                return Err(josh_error(&format!(
+                    "rejecting to amend {:?} with conflicting changes",
+                    msg
+                )))?
+            }
+        };
+
+        let mut walk = transaction.repo().revwalk()?;
+        walk.set_sorting(git2::Sort::REVERSE | git2::Sort::TOPOLOGICAL)?;
+        walk.push(rewritten)?;
+        walk.hide(original_target)?;
+        Ok(walk
+            .map(|id| Ok(id?.to_string()))
+            .collect::<JoshResult<_>>()?)
+    }
 }
 
 #[graphql_object(context = Context)]
@@ -714,7 +2572,38 @@ impl Repository {
         &self.name
     }
 
-    fn refs(&self, context: &Context, pattern: Option<String>) -> FieldResult<Vec<Reference>> {
+    /// Encoded name as used in ref names, for example in
+    /// `refs/josh/upstream/<namespace>.git/refs/heads/master`
+    fn namespace(&self) -> String {
+        to_ns(&self.name)
+    }
+
+    /// Branch `HEAD` points to, like "refs/heads/master", if it is set
+    fn default_branch(&self, context: &Context) -> FieldResult<Option<String>> {
+        let transaction = context.transaction.lock()?;
+        let prefix = format!("refs/josh/upstream/{}.git/", to_ns(&self.name));
+        let head = ok_or!(
+            transaction
+                .repo()
+                .find_reference(&format!("{}HEAD", prefix)),
+            {
+                return Ok(None);
+            }
+        );
+        Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix(&prefix))
+            .map(|target| target.to_string()))
+    }
+
+    /// Refs deleted upstream are pruned on fetch. With `includeStale: false`
+    /// refs that are left pointing to a commit that does not exist are omitted as well.
+    fn refs(
+        &self,
+        context: &Context,
+        pattern: Option<String>,
+        include_stale: Option<bool>,
+    ) -> FieldResult<Vec<Reference>> {
         let transaction = context.transaction.lock()?;
         let refname = format!(
             "refs/josh/upstream/{}.git/{}",
@@ -730,6 +2619,12 @@ impl Repository {
             let r = reference?;
             let name = r.name().ok_or(josh_error("reference without name"))?;
 
+            if let Some(false) = include_stale {
+                if r.peel_to_commit().is_err() {
+                    continue;
+                }
+            }
+
             refs.push(Reference {
                 refname: name.to_string(),
             });
@@ -738,21 +2633,212 @@ impl Repository {
         Ok(refs)
     }
 
-    fn rev(&self, context: &Context, at: String, filter: Option<String>) -> FieldResult<Revision> {
-        let rev = format!("refs/josh/upstream/{}.git/{}", to_ns(&self.name), at);
+    /// Distinct next path segments of the branch names below `under`, sorted.
+    /// With branches "feature/a" and "release/1", `under: ""` returns
+    /// `["feature", "release"]` and `under: "feature"` returns `["a"]`.
+    fn ref_prefixes(&self, context: &Context, under: Option<String>) -> FieldResult<Vec<String>> {
+        let transaction = context.transaction.lock()?;
+        let under = under.unwrap_or("".to_string());
+        let under = under.trim_matches('/');
+        let prefix = if under.is_empty() {
+            format!("refs/josh/upstream/{}.git/refs/heads/", to_ns(&self.name))
+        } else {
+            format!(
+                "refs/josh/upstream/{}.git/refs/heads/{}/",
+                to_ns(&self.name),
+                under
+            )
+        };
+
+        let mut segments = std::collections::BTreeSet::new();
+        for reference in transaction
+            .repo()
+            .references_glob(&format!("{}*", prefix))?
+        {
+            let r = reference?;
+            let name = r.name().ok_or(josh_error("reference without name"))?;
+            if let Some(rest) = name.strip_prefix(&prefix) {
+                if let Some(segment) = rest.split('/').next() {
+                    segments.insert(segment.to_string());
+                }
+            }
+        }
+
+        Ok(segments.into_iter().collect())
+    }
 
+    /// Warnings of `filter` for the tip of every branch, like `Revision.warnings`
+    fn warnings(&self, context: &Context, filter: String) -> FieldResult<Vec<BranchWarnings>> {
+        let filter = context.parse_filter(&filter)?;
         let transaction = context.transaction.lock()?;
-        let id = if let Ok(id) = git2::Oid::from_str(&at) {
-            id
+        let prefix = format!("refs/josh/upstream/{}.git/", to_ns(&self.name));
+
+        let mut result = vec![];
+        for reference in transaction
+            .repo()
+            .references_glob(&format!("{}refs/heads/*", prefix))?
+        {
+            let r = reference?;
+            let name = r.name().ok_or(josh_error("reference without name"))?;
+            let commit = ok_or!(r.peel_to_commit(), { continue });
+            let warnings = filter::compute_warnings(&transaction, filter, commit.tree()?)
+                .iter()
+                .map(|warn| Warning {
+                    text: warn.to_string(),
+                })
+                .collect();
+            result.push(BranchWarnings {
+                branch: name.strip_prefix(&prefix).unwrap_or(name).to_string(),
+                warnings: warnings,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// `filterId` can be used instead of `filter` to pass the id of a filter
+    /// previously returned by `Revision.filterId`, avoiding to parse the spec again.
+    fn rev(
+        &self,
+        context: &Context,
+        at: String,
+        filter: Option<String>,
+        filter_id: Option<String>,
+    ) -> FieldResult<Revision> {
+        let filter = if let Some(filter_id) = filter_id {
+            filter::from_id(git2::Oid::from_str(&filter_id)?)
+                .ok_or(josh_error(&format!("unknown filter id {:?}", filter_id)))?
         } else {
-            transaction.repo().revparse_single(&rev)?.id()
+            context.parse_filter(&filter.unwrap_or(":/".to_string()))?
         };
 
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &self.name, &at)?;
+
         Ok(Revision {
-            filter: filter::parse(&filter.unwrap_or(":/".to_string()))?,
+            filter: filter,
             commit_id: id,
         })
     }
+
+    /// Spec of the filter defined as `name` in the `.josh` file at the root of the
+    /// tree of `at` (default the branch `HEAD` points to). The file has the format
+    /// of a workspace file, with `name = :filter` entries:
+    ///
+    /// ```text
+    /// # Views of this repo
+    /// docs = :/docs
+    /// api = :[
+    ///     ::src/api/
+    ///     ::proto/
+    /// ]
+    /// ```
+    fn named_filter(
+        &self,
+        name: String,
+        at: Option<String>,
+        context: &Context,
+    ) -> FieldResult<String> {
+        let at = at.unwrap_or("HEAD".to_string());
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &self.name, &at)?;
+        let tree = transaction.repo().find_commit(id)?.tree()?;
+
+        let blob = tree
+            .get_path(std::path::Path::new(".josh"))
+            .and_then(|entry| transaction.repo().find_blob(entry.id()))
+            .map_err(|_| josh_error(&format!("no .josh file in {:?}", at)))?;
+        let content = std::str::from_utf8(blob.content())
+            .map_err(|_| josh_error(".josh is not valid UTF-8"))?;
+
+        let spec = filter::file_entries(content)?
+            .into_iter()
+            .find(|(path, _)| *path == name)
+            .map(|(_, spec)| spec)
+            .ok_or(josh_error(&format!("no filter {:?} in .josh", name)))?;
+        Ok(filter::spec(filter::parse(&spec)?))
+    }
+
+    /// The `limit` (default 10) most recent commits on any branch, newest first.
+    /// With `filter` commits that don't change the filtered content are skipped.
+    fn recent_commits(
+        &self,
+        context: &Context,
+        limit: Option<i32>,
+        filter: Option<String>,
+    ) -> FieldResult<Vec<Revision>> {
+        let filter = context.parse_filter(&filter.unwrap_or(":/".to_string()))?;
+        let limit = std::cmp::max(limit.unwrap_or(10), 0) as usize;
+        let transaction = context.transaction.lock()?;
+        let repo = transaction.repo();
+
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(git2::Sort::TIME)?;
+        let refname = format!("refs/josh/upstream/{}.git/refs/heads/*", to_ns(&self.name));
+        for reference in repo.references_glob(&refname)? {
+            if let Ok(commit) = reference?.peel_to_commit() {
+                walk.push(commit.id())?;
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        let mut revs = vec![];
+        for id in walk {
+            if revs.len() >= limit {
+                break;
+            }
+            let commit = repo.find_commit(id?)?;
+            let filtered = filter::apply_to_commit(filter, &commit, &transaction)?;
+            if filtered == git2::Oid::zero() || seen.contains(&filtered) {
+                continue;
+            }
+            if let (1, Some(parent)) = (commit.parent_count(), commit.parents().next()) {
+                if filter::apply_to_commit(filter, &parent, &transaction)? == filtered {
+                    continue;
+                }
+            }
+            seen.insert(filtered);
+            revs.push(Revision {
+                filter: filter,
+                commit_id: commit.id(),
+            });
+        }
+        Ok(revs)
+    }
+}
+
+#[derive(juniper::GraphQLInputObject)]
+struct RepoRefInput {
+    repo: String,
+    at: String,
+}
+
+/// Resolve `at`, either a commit id or a ref in the upstream namespace of `repo`
+fn resolve_upstream(
+    context: &Context,
+    transaction: &cache::Transaction,
+    repo: &str,
+    at: &str,
+) -> JoshResult<git2::Oid> {
+    if let Ok(id) = git2::Oid::from_str(&at) {
+        return Ok(id);
+    }
+    let rev = format!("refs/josh/upstream/{}.git/{}", to_ns(&repo), at);
+    context.resolve_rev(transaction, &rev)
+}
+
+/// Resolve `rev`, either a commit id or a revision relative to the ref namespace
+/// of the transaction. For the schema of a repo that is its upstream namespace,
+/// so branch names like "refs/heads/master" refer to the upstream branches.
+fn resolve_in_namespace(
+    context: &Context,
+    transaction: &cache::Transaction,
+    rev: &str,
+) -> JoshResult<git2::Oid> {
+    if let Ok(id) = git2::Oid::from_str(&rev) {
+        return Ok(id);
+    }
+    context.resolve_rev(transaction, &transaction.refname(rev))
 }
 
 pub struct Query;
@@ -789,6 +2875,526 @@ impl Query {
 
         return Ok(repos.into_iter().map(|x| Repository { name: x }).collect());
     }
+
+    /// Whether changes to the output of the filter `spec` can be mapped back,
+    /// meaning pushing to it is possible
+    fn is_reversible(context: &Context, spec: String) -> FieldResult<bool> {
+        Ok(filter::is_reversible(context.parse_filter(&spec)?))
+    }
+
+    /// Paths of the upstream tree the filter `spec` reads from, for example to
+    /// configure a sparse checkout. `[""]` means the whole tree is needed.
+    fn filter_inputs(context: &Context, spec: String) -> FieldResult<Vec<String>> {
+        Ok(filter::input_paths(context.parse_filter(&spec)?)
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Upstream ref `refname` (like "refs/heads/master") of `repo`.
+    /// Use `rev(filter: ...)` on the result to get a filtered revision.
+    fn r#ref(context: &Context, repo: String, refname: String) -> FieldResult<Reference> {
+        let transaction = context.transaction.lock()?;
+        let name = format!("refs/josh/upstream/{}.git/{}", to_ns(&repo), refname);
+        if transaction.repo().find_reference(&name).is_err() {
+            return Err(josh_error(&format!("no ref {:?} in {:?}", refname, repo)))?;
+        }
+        Ok(Reference { refname: name })
+    }
+
+    /// Id of the tree resulting from applying the filter `spec` to the tree
+    /// with id `tree`. No commits are involved, so no history is walked.
+    fn filter_tree(context: &Context, tree: String, spec: String) -> FieldResult<String> {
+        let filter = context.parse_filter(&spec)?;
+        let transaction = context.transaction.lock()?;
+        let tree = git2::Oid::from_str(&tree)?;
+        Ok(filter::apply_to_tree(filter, tree, &transaction)?.to_string())
+    }
+
+    /// Unified diff from the output of the filter `from` to the output of the
+    /// filter `to` when applied to the same revision `at` of `repo`
+    fn filter_patch(
+        context: &Context,
+        repo: String,
+        at: String,
+        from: String,
+        to: String,
+    ) -> FieldResult<String> {
+        let from = context.parse_filter(&from)?;
+        let to = context.parse_filter(&to)?;
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &repo, &at)?;
+
+        let tree = transaction.repo().find_commit(id)?.tree()?;
+        let old_tree = filter::apply(&transaction, from, tree.clone())?;
+        let new_tree = filter::apply(&transaction, to, tree)?;
+        let diff = transaction
+            .repo()
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)?;
+
+        let mut patch = vec![];
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if let '+' | '-' | ' ' = line.origin() {
+                patch.push(line.origin() as u8);
+            }
+            patch.extend_from_slice(line.content());
+            true
+        })?;
+        Ok(String::from_utf8_lossy(&patch).to_string())
+    }
+
+    /// Revisions `at` of several repos, all with the same `filter`
+    fn multi_rev(
+        context: &Context,
+        refs: Vec<RepoRefInput>,
+        filter: Option<String>,
+    ) -> FieldResult<Vec<Revision>> {
+        let filter = context.parse_filter(&filter.unwrap_or(":/".to_string()))?;
+        let transaction = context.transaction.lock()?;
+        let mut revs = vec![];
+        for r in refs {
+            revs.push(Revision {
+                filter: filter,
+                commit_id: resolve_upstream(context, &transaction, &r.repo, &r.at)?,
+            });
+        }
+        Ok(revs)
+    }
+
+    /// Number and total size of the files before and after applying the filter `spec`
+    fn filter_stats(
+        context: &Context,
+        repo: String,
+        at: String,
+        spec: String,
+    ) -> FieldResult<FilterStats> {
+        let filter = context.parse_filter(&spec)?;
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &repo, &at)?;
+
+        let tree = transaction.repo().find_commit(id)?.tree()?;
+        let (input_paths, input_bytes) = tree_stats(transaction.repo(), &tree)?;
+        let tree = filter::apply(&transaction, filter, tree)?;
+        let (output_paths, output_bytes) = tree_stats(transaction.repo(), &tree)?;
+
+        Ok(FilterStats {
+            input_paths,
+            output_paths,
+            input_bytes,
+            output_bytes,
+        })
+    }
+
+    /// Total size of the files after applying the filter `spec` relative to the size
+    /// before, `0.12` means the filter keeps 12% of the bytes. 0 for an empty tree.
+    fn filter_reduction(
+        context: &Context,
+        repo: String,
+        at: String,
+        spec: String,
+    ) -> FieldResult<f64> {
+        let filter = context.parse_filter(&spec)?;
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &repo, &at)?;
+
+        let tree = transaction.repo().find_commit(id)?.tree()?;
+        let (_, input_bytes) = tree_stats(transaction.repo(), &tree)?;
+        let tree = filter::apply(&transaction, filter, tree)?;
+        let (_, output_bytes) = tree_stats(transaction.repo(), &tree)?;
+
+        if input_bytes == 0.0 {
+            return Ok(0.0);
+        }
+        Ok(output_bytes / input_bytes)
+    }
+
+    /// Sorted ids of the commits that have markers for `topic`
+    fn marked_commits(context: &Context, topic: String) -> FieldResult<Vec<String>> {
+        let transaction = context.transaction.lock()?;
+        let refname = transaction.refname("refs/josh/meta");
+
+        let tree = match transaction.repo().revparse_single(&refname) {
+            Ok(r) => transaction.repo().find_commit(r.id())?.tree()?,
+            Err(_) => return Ok(vec![]),
+        };
+        let topic_tree = match tree.get_path(&std::path::Path::new(&escape_topic(&topic)).join("~"))
+        {
+            Ok(entry) => transaction.repo().find_tree(entry.id())?,
+            Err(_) => return Ok(vec![]),
+        };
+
+        // The commit directories are on the fourth level, see `marker_path`
+        let mut commits = vec![];
+        topic_tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            if root.matches('/').count() < 3 {
+                return 0;
+            }
+            if let (Some(git2::ObjectType::Tree), Some(name)) = (entry.kind(), entry.name()) {
+                if git2::Oid::from_str(name).is_ok() {
+                    commits.push(name.to_string());
+                }
+            }
+            1
+        })?;
+        commits.sort();
+
+        Ok(commits)
+    }
+
+    /// A simple filter producing the tree with id `target` from the revision `at`:
+    /// a subdirectory filter `:/a`, a prefix filter `:prefix=b`, or `:/a:prefix=b`.
+    /// Filters with a shorter prefix are preferred, then those with a shorter
+    /// subdirectory. `null` if no such filter exists.
+    fn infer_filter(
+        context: &Context,
+        repo: String,
+        at: String,
+        target: String,
+    ) -> FieldResult<Option<String>> {
+        let transaction = context.transaction.lock()?;
+        let id = resolve_upstream(context, &transaction, &repo, &at)?;
+        let tree = transaction.repo().find_commit(id)?.tree()?;
+        let target = transaction
+            .repo()
+            .find_tree(git2::Oid::from_str(&target)?)?;
+
+        // Subdirectories of the input by tree id, keeping the shortest path for each
+        let mut subdirs = std::collections::HashMap::new();
+        subdirs.insert(tree.id(), std::path::PathBuf::new());
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            if let (Some(git2::ObjectType::Tree), Some(name)) = (entry.kind(), entry.name()) {
+                let path = std::path::Path::new(root).join(name);
+                let known = subdirs.entry(entry.id()).or_insert(path.clone());
+                if path.components().count() < known.components().count() {
+                    *known = path;
+                }
+            }
+            0
+        })?;
+
+        // A prefix shows up as a chain of directories with only one entry
+        let mut prefix = std::path::PathBuf::new();
+        let mut inner = target;
+        loop {
+            if let Some(subdir) = subdirs.get(&inner.id()) {
+                let filter = filter::chain(filter::subdir(subdir), filter::prefix(&prefix));
+                return Ok(Some(filter::spec(filter)));
+            }
+            let next = match (inner.len(), inner.get(0)) {
+                (1, Some(entry)) if entry.kind() == Some(git2::ObjectType::Tree) => {
+                    prefix.push(entry.name().ok_or(josh_error("no name"))?);
+                    entry.id()
+                }
+                _ => return Ok(None),
+            };
+            inner = transaction.repo().find_tree(next)?;
+        }
+    }
+
+    /// Resolve `ref` in the upstream namespace of `repo` and also return the
+    /// corresponding commit in the history filtered with `filter`
+    fn resolve_ref(
+        context: &Context,
+        repo: String,
+        r#ref: String,
+        filter: String,
+    ) -> FieldResult<RefResolution> {
+        let filter = context.parse_filter(&filter)?;
+        let transaction = context.transaction.lock()?;
+        let original = resolve_upstream(context, &transaction, &repo, &r#ref)?;
+        let commit = transaction.repo().find_commit(original)?;
+        let filtered = filter::apply_to_commit(filter, &commit, &transaction)?;
+
+        Ok(RefResolution {
+            original_oid: original.to_string(),
+            filtered_oid: filtered.to_string(),
+        })
+    }
+
+    /// Commits, oldest first, of the filtered history `from` that are not in `onto`
+    /// (default the branch `HEAD` points to) and don't survive a round trip through `filter`:
+    /// their tree is unapplied onto the tree of `onto` and filtered again.
+    /// `missingPaths` are the files that are lost or changed on the way,
+    /// `extraPaths` files that show up that were not in the commit.
+    fn check_reversible(
+        context: &Context,
+        repo: String,
+        filter: String,
+        from: String,
+        onto: Option<String>,
+    ) -> FieldResult<Vec<ReversibilityIssue>> {
+        let filter = context.parse_filter(&filter)?;
+        let transaction = context.transaction.lock()?;
+        let onto = onto.unwrap_or("HEAD".to_string());
+        let original_target = resolve_upstream(context, &transaction, &repo, &onto)?;
+        let original_target = transaction.repo().find_commit(original_target)?;
+        let old = filter::apply_to_commit(filter, &original_target, &transaction)?;
+        let new = resolve_upstream(context, &transaction, &repo, &from)?;
+
+        let mut walk = transaction.repo().revwalk()?;
+        walk.set_sorting(git2::Sort::REVERSE | git2::Sort::TOPOLOGICAL)?;
+        walk.push(new)?;
+        if old != git2::Oid::zero() {
+            walk.hide(old)?;
+        }
+
+        let mut issues = vec![];
+        for id in walk {
+            let commit = transaction.repo().find_commit(id?)?;
+            let tree = commit.tree()?;
+            let unapplied =
+                filter::unapply(&transaction, filter, tree.clone(), original_target.tree()?)?;
+            let reapplied = filter::apply(&transaction, filter, unapplied)?;
+            if reapplied.id() == tree.id() {
+                continue;
+            }
+
+            let diff = transaction
+                .repo()
+                .diff_tree_to_tree(Some(&tree), Some(&reapplied), None)?;
+            let mut missing_paths = vec![];
+            let mut extra_paths = vec![];
+            for delta in diff.deltas() {
+                let path = |file: git2::DiffFile| {
+                    file.path()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                };
+                match delta.status() {
+                    git2::Delta::Added => extra_paths.push(path(delta.new_file())),
+                    _ => missing_paths.push(path(delta.old_file())),
+                }
+            }
+            issues.push(ReversibilityIssue {
+                commit: commit.id().to_string(),
+                missing_paths,
+                extra_paths,
+            });
+        }
+
+        Ok(issues)
+    }
+
+    /// Page of the commits of `repo` for which the result of `filter` is known,
+    /// ordered by the original commit id. Pass the last `original` of a page as
+    /// `after` to get the next one. At most `MAPPINGS_LIMIT` entries are returned.
+    fn mappings(
+        context: &Context,
+        repo: String,
+        filter: String,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> FieldResult<Vec<Mapping>> {
+        let filter = context.parse_filter(&filter)?;
+        let first = std::cmp::min(
+            std::cmp::max(first.unwrap_or(100), 0) as usize,
+            MAPPINGS_LIMIT,
+        );
+        let after = after.map(|x| git2::Oid::from_str(&x)).transpose()?;
+        let transaction = context.transaction.lock()?;
+
+        // The forward map is shared by all repos, so only commits reachable from
+        // the refs of `repo` are returned. The reachable commits are cached until
+        // the refs change, so getting the next pages doesn't walk the history again.
+        let mut tips = vec![];
+        let refname = format!("refs/josh/upstream/{}.git/refs/*", to_ns(&repo));
+        for reference in transaction.repo().references_glob(&refname)? {
+            if let Ok(commit) = reference?.peel_to_commit() {
+                tips.push(commit.id());
+            }
+        }
+        let in_repo = transaction.reachable(&tips)?;
+
+        let mut mappings = vec![];
+        transaction.mappings(filter, after, &mut |original, filtered| {
+            if mappings.len() >= first {
+                return false;
+            }
+            if in_repo.contains(&original) {
+                mappings.push(Mapping {
+                    original: original.to_string(),
+                    filtered: if filtered == git2::Oid::zero() {
+                        None
+                    } else {
+                        Some(filtered.to_string())
+                    },
+                });
+            }
+            true
+        });
+        Ok(mappings)
+    }
+}
+
+const MAPPINGS_LIMIT: usize = 1000;
+
+#[derive(juniper::GraphQLObject)]
+pub struct RefResolution {
+    original_oid: String,
+    filtered_oid: String,
+}
+
+/// `filtered` is null if nothing of the commit is left after filtering
+#[derive(juniper::GraphQLObject)]
+pub struct Mapping {
+    original: String,
+    filtered: Option<String>,
+}
+
+#[derive(juniper::GraphQLObject)]
+pub struct FilterSurvival {
+    spec: String,
+    survives: bool,
+    /// Path of the file in the output of the filter, if it survives
+    path: Option<String>,
+}
+
+/// A `key: value` line of the trailer block of a commit message, indented
+/// continuation lines are joined to the value
+#[derive(juniper::GraphQLObject)]
+pub struct Trailer {
+    key: String,
+    value: String,
+}
+
+/// Like git the last paragraph is the trailer block if every line in it is either
+/// `key: value` or, indented, a continuation of the previous value. The subject
+/// paragraph never counts as a trailer block.
+fn parse_trailers(message: &str) -> Vec<Trailer> {
+    let paragraphs: Vec<Vec<&str>> = message
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .split(|line| line.is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.to_vec())
+        .collect();
+
+    if paragraphs.len() < 2 {
+        return vec![];
+    }
+
+    let mut trailers: Vec<Trailer> = vec![];
+    for line in paragraphs[paragraphs.len() - 1].iter() {
+        if line.starts_with(|c: char| c.is_whitespace()) {
+            if let Some(trailer) = trailers.last_mut() {
+                trailer.value = format!("{} {}", trailer.value, line.trim());
+                continue;
+            }
+            return vec![];
+        }
+        let (key, value) = if let Some(i) = line.find(':') {
+            (&line[..i], &line[i + 1..])
+        } else {
+            return vec![];
+        };
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return vec![];
+        }
+        trailers.push(Trailer {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        });
+    }
+    trailers
+}
+
+/// `line` is 1 based, `commit` is the id of a filtered commit
+#[derive(juniper::GraphQLObject)]
+pub struct BlameLine {
+    line: i32,
+    commit: String,
+    author_name: String,
+    author_email: String,
+}
+
+/// `hasMore` is true if there are more paths after the last one of `items`
+#[derive(juniper::GraphQLObject)]
+#[graphql(context = Context)]
+pub struct PathPage {
+    items: Vec<Path>,
+    has_more: bool,
+}
+
+/// `status` is one of "added", "modified", "deleted" and "renamed",
+/// `old_path` is only set for renamed paths
+#[derive(juniper::GraphQLObject)]
+#[graphql(context = Context)]
+pub struct ChangedPath {
+    status: String,
+    path: Path,
+    old_path: Option<String>,
+}
+
+/// `oldPath` is null for added files and `newPath` for deleted ones
+#[derive(juniper::GraphQLObject)]
+pub struct FileDiff {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    insertions: i32,
+    deletions: i32,
+    /// Unified diff of the file
+    patch: String,
+}
+
+#[derive(juniper::GraphQLObject)]
+pub struct ReversibilityIssue {
+    commit: String,
+    missing_paths: Vec<String>,
+    extra_paths: Vec<String>,
+}
+
+/// `kind` is "blob", "tree" or "commit" for submodules
+#[derive(juniper::GraphQLObject)]
+pub struct EntryHash {
+    name: String,
+    oid: String,
+    kind: String,
+}
+
+/// `filesChanged` counts every path at most once, even if several commits changed it
+#[derive(juniper::GraphQLObject)]
+pub struct RangeStats {
+    commits: i32,
+    files_changed: i32,
+    insertions: i32,
+    deletions: i32,
+}
+
+/// Byte counts are floats because they can exceed the range of a GraphQL `Int`
+#[derive(juniper::GraphQLObject)]
+pub struct FilterStats {
+    input_paths: i32,
+    output_paths: i32,
+    input_bytes: f64,
+    output_bytes: f64,
+}
+
+fn tree_stats(repo: &git2::Repository, tree: &git2::Tree) -> JoshResult<(i32, f64)> {
+    let odb = repo.odb()?;
+    let mut paths = 0;
+    let mut bytes = 0;
+    let mut error = None;
+    tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+        if let Some(git2::ObjectType::Blob) = entry.kind() {
+            match odb.read_header(entry.id()) {
+                Ok((size, _)) => {
+                    paths += 1;
+                    bytes += size;
+                }
+                Err(e) => {
+                    error = Some(e);
+                    return -1;
+                }
+            }
+        }
+        0
+    })
+    .ok();
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+    Ok((paths, bytes as f64))
 }
 
 regex_parsed!(
@@ -803,6 +3409,12 @@ pub type Schema =
 pub fn context(transaction: cache::Transaction) -> Context {
     Context {
         transaction: std::sync::Arc::new(std::sync::Mutex::new(transaction)),
+        fallback_signature: ("josh".to_string(), "josh@localhost".to_string()),
+        filters: std::sync::Mutex::new(std::collections::HashMap::new()),
+        max_parse_size: 10 * 1024 * 1024,
+        max_raw_bytes_size: 1024 * 1024,
+        entries: Some(std::sync::Mutex::new(std::collections::HashMap::new())),
+        revs: std::sync::Mutex::new(std::collections::HashMap::new()),
     }
 }
 
@@ -836,3 +3448,6 @@ pub fn repo_schema(name: &str) -> RepoSchema {
         EmptySubscription::new(),
     )
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,2984 @@
+use super::*;
+use crate::tests::test_repo;
+
+type Errors = Vec<juniper::ExecutionError<juniper::DefaultScalarValue>>;
+
+/// Commit `files` on top of the tree of the first of `parents`, a `None`
+/// content removes the path
+fn commit_with(
+    repo: &git2::Repository,
+    signature: &git2::Signature,
+    message: &str,
+    parents: &[git2::Oid],
+    files: &[(&str, Option<&str>)],
+) -> git2::Oid {
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|id| repo.find_commit(*id).unwrap())
+        .collect();
+    let mut tree = parents
+        .first()
+        .map(|p| p.tree().unwrap())
+        .unwrap_or_else(|| filter::tree::empty(repo));
+    for (path, content) in files {
+        let blob = content
+            .map(|c| repo.blob(c.as_bytes()).unwrap())
+            .unwrap_or_else(git2::Oid::zero);
+        tree =
+            filter::tree::insert(repo, &tree, std::path::Path::new(path), blob, 0o0100644).unwrap();
+    }
+    let parents: Vec<_> = parents.iter().collect();
+    repo.commit(None, signature, signature, message, &tree, &parents)
+        .unwrap()
+}
+
+/// Commit `files` on top of the tree of the first of `parents`
+fn commit_files(
+    repo: &git2::Repository,
+    parents: &[git2::Oid],
+    files: &[(&str, &str)],
+) -> git2::Oid {
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let files: Vec<_> = files.iter().map(|(p, c)| (*p, Some(*c))).collect();
+    commit_with(repo, &signature, "msg", parents, &files)
+}
+
+fn commit_file(repo: &git2::Repository, path: &str, content: &str) -> git2::Oid {
+    commit_files(repo, &[], &[(path, content)])
+}
+
+fn repo_context(repo: git2::Repository) -> Context {
+    context(cache::Transaction::new(repo, None))
+}
+
+/// Execute `query` and return the result as JSON together with the errors
+fn execute<Q, M>(
+    schema: &juniper::RootNode<'static, Q, M, EmptySubscription<Context>>,
+    context: &Context,
+    query: &str,
+) -> (serde_json::Value, Errors)
+where
+    Q: juniper::GraphQLType<Context = Context, TypeInfo = ()>,
+    M: juniper::GraphQLType<Context = Context, TypeInfo = ()>,
+{
+    let (res, errors) =
+        juniper::execute_sync(query, None, schema, &juniper::Variables::new(), context).unwrap();
+    (serde_json::to_value(&res).unwrap(), errors)
+}
+
+/// Run `query` against the schema of the repo "repo", it must not fail
+fn run(context: &Context, query: &str) -> serde_json::Value {
+    let (res, errors) = execute(&repo_schema("repo"), context, query);
+    assert!(errors.is_empty(), "{:?}", errors);
+    res
+}
+
+/// Run `query` against the root schema, it must not fail
+fn run_root(context: &Context, query: &str) -> serde_json::Value {
+    let (res, errors) = execute(&schema(), context, query);
+    assert!(errors.is_empty(), "{:?}", errors);
+    res
+}
+
+/// The errors of running `query` against the schema of the repo "repo"
+fn run_errors(context: &Context, query: &str) -> Errors {
+    execute(&repo_schema("repo"), context, query).1
+}
+
+#[test]
+fn meta_without_user_config() {
+    let repo = test_repo("meta_without_user_config");
+    assert!(repo.signature().is_err());
+    let commit = commit_file(&repo, "a/b", "content");
+
+    let context = repo_context(repo);
+    let res = run(
+        &context,
+        &format!(
+            r#"mutation {{ meta(commit: "{}", topic: "t", add: [{{ path: "a/b", data: ["{{}}"] }}]) }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res, json!({ "meta": true }));
+
+    let transaction = context.transaction.lock().unwrap();
+    let meta = transaction
+        .repo()
+        .find_reference("refs/josh/meta")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(meta.author().name(), Some("josh"));
+    assert_eq!(meta.author().email(), Some("josh@localhost"));
+}
+
+#[test]
+fn document_patch_test() {
+    let repo = test_repo("document_patch_test");
+    let commit = commit_file(&repo, "a.json", r#"{"a": {"b": [1, 2]}, "c": "x"}"#);
+    let context = repo_context(repo);
+    let query = |ops: serde_json::Value| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a.json") {{ json {{ patch(ops: {}) {{ flatten {{ path value }} }} }} }} }} }}"#,
+            commit,
+            serde_json::to_string(&ops.to_string()).unwrap()
+        )
+    };
+
+    let res = run(
+        &context,
+        &query(json!([
+            {"op": "test", "path": "/c", "value": "x"},
+            {"op": "add", "path": "/a/b/-", "value": 3},
+            {"op": "replace", "path": "/a/b/0", "value": 0},
+            {"op": "move", "from": "/c", "path": "/d"},
+            {"op": "copy", "from": "/d", "path": "/a/e"},
+            {"op": "remove", "path": "/a/b/1"},
+        ])),
+    );
+    assert_eq!(
+        res["rev"]["file"]["json"]["patch"]["flatten"],
+        json!([
+            {"path": "/a/b/0", "value": "0"},
+            {"path": "/a/b/1", "value": "3"},
+            {"path": "/a/e", "value": "x"},
+            {"path": "/d", "value": "x"},
+        ])
+    );
+
+    for ops in [
+        json!([{"op": "test", "path": "/c", "value": "y"}]),
+        json!([{"op": "remove", "path": "/x"}]),
+        json!([{"op": "add", "path": "/a/b/5", "value": 1}]),
+    ]
+    .iter()
+    {
+        let errors = run_errors(&context, &query(ops.clone()));
+        assert!(
+            errors[0]
+                .error()
+                .message()
+                .starts_with("JoshError(json patch: "),
+            "{:?}",
+            errors
+        );
+    }
+    assert_eq!(run_errors(&context, &query(json!({"op": "add"}))).len(), 1);
+}
+
+#[test]
+fn flatten_test() {
+    let mut entries = vec![];
+    flatten_value(
+        "",
+        &json!({"a": {"b": [1, {"c": true}], "d/e": "x"}, "f": null}),
+        &mut entries,
+    );
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|x| (x.path.as_str(), x.value.as_str()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("/a/b/0", "1"),
+            ("/a/b/1/c", "true"),
+            ("/a/d~1e", "x"),
+            ("/f", "null"),
+        ]
+    );
+}
+
+#[test]
+fn archive_test() {
+    let repo = test_repo("archive_test");
+    // Long enough to need a GNU long name, with multi-byte characters
+    // around the offsets of the ustar name fields
+    let long = format!("a/{}/{}.txt", "ä".repeat(60), "ö".repeat(60));
+    let tree = repo
+        .find_commit(commit_files(
+            &repo,
+            &[],
+            &[("a/b/c.txt", "content"), (&long, "long")],
+        ))
+        .unwrap()
+        .tree_id();
+    // 2200-01-01, after the last date zip can store. Written by hand because
+    // libgit2 truncates the time of signatures to 32 bits.
+    let commit = repo
+        .odb()
+        .unwrap()
+        .write(
+            git2::ObjectType::Commit,
+            format!(
+                "tree {}\nauthor Josh <josh@example.com> 7258118400 +0000\n\
+                 committer Josh <josh@example.com> 7258118400 +0000\n\nmsg\n",
+                tree
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+    let context = repo_context(repo);
+    let query = |format: &str| {
+        let res = run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: ":/a") {{ archive(format: "{}") }} }}"#,
+                commit, format
+            ),
+        );
+        base64::decode(res["rev"]["archive"].as_str().unwrap()).unwrap()
+    };
+    let long = &long[2..];
+    let dir = format!("{}/", "ä".repeat(60));
+
+    let tar = query("tar");
+    let mut archive = tar::Archive::new(&tar[..]);
+    let entries: Vec<_> = archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut content).unwrap();
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            (path, entry.header().mtime().unwrap(), content)
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("b/".to_string(), 7258118400, "".to_string()),
+            ("b/c.txt".to_string(), 7258118400, "content".to_string()),
+            (dir.clone(), 7258118400, "".to_string()),
+            (long.to_string(), 7258118400, "long".to_string()),
+        ]
+    );
+
+    let zip = query("zip");
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(zip)).unwrap();
+    let mut entries = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut content).unwrap();
+        let year = entry.last_modified().unwrap().year();
+        entries.push((entry.name().to_string(), year, content));
+    }
+    assert_eq!(
+        entries,
+        vec![
+            ("b/".to_string(), 2107, "".to_string()),
+            ("b/c.txt".to_string(), 2107, "content".to_string()),
+            (dir.clone(), 2107, "".to_string()),
+            (long.to_string(), 2107, "long".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn filter_stats_test() {
+    let repo = test_repo("filter_stats_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let query = format!(
+        r#"query {{ filterStats(repo: "repo", at: "{}", spec: ":/x") {{
+            inputPaths outputPaths inputBytes outputBytes }} }}"#,
+        commit
+    );
+    let res = run_root(&context, &query);
+    assert_eq!(
+        res,
+        json!({ "filterStats": {
+            "inputPaths": 1,
+            "outputPaths": 0,
+            "inputBytes": 7.0,
+            "outputBytes": 0.0,
+        }})
+    );
+}
+
+#[test]
+fn marked_commits_test() {
+    let repo = test_repo("marked_commits_test");
+    let a = commit_file(&repo, "a", "a");
+    let b = commit_file(&repo, "b", "b");
+    let context = repo_context(repo);
+    let marked = |topic: &str| {
+        let res = run_root(
+            &context,
+            &format!(r#"query {{ markedCommits(topic: "{}") }}"#, topic),
+        );
+        res["markedCommits"].clone()
+    };
+
+    assert_eq!(marked("t"), json!([]));
+    for (commit, topic) in [(a, "t"), (b, "t"), (b, "u/v")].iter() {
+        run(
+            &context,
+            &format!(
+                r#"mutation {{ meta(commit: "{}", topic: "{}", add: [{{ path: "x/y", data: ["{{}}"] }}]) }}"#,
+                commit, topic
+            ),
+        );
+    }
+
+    let mut expected = vec![a.to_string(), b.to_string()];
+    expected.sort();
+    assert_eq!(marked("t"), json!(expected));
+    assert_eq!(marked("u/v"), json!([b.to_string()]));
+    assert_eq!(marked("u"), json!([]));
+}
+
+#[test]
+fn infer_filter_test() {
+    let repo = test_repo("infer_filter_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("a/b/f", "a/b/f"),
+            ("a/b/g", "a/b/g"),
+            ("c/d/f", "c/d/f"),
+            ("h", "h"),
+        ],
+    );
+    let tree = repo.find_commit(commit).unwrap().tree_id();
+    let context = repo_context(repo);
+    let targets: Vec<_> = {
+        let transaction = context.transaction.lock().unwrap();
+        [":/a/b", ":prefix=x/y", ":/a:prefix=x", ":/", ":/a/b:/f"]
+            .iter()
+            .map(|spec| {
+                filter::apply_to_tree(filter::parse(spec).unwrap(), tree, &transaction).unwrap()
+            })
+            .collect()
+    };
+
+    for (target, expected) in targets.iter().zip(
+        [
+            json!(":/a/b"),
+            json!(":prefix=x/y"),
+            json!(":/a:prefix=x"),
+            json!(":/"),
+            json!(null),
+        ]
+        .iter(),
+    ) {
+        let res = run_root(
+            &context,
+            &format!(
+                r#"query {{ inferFilter(repo: "repo", at: "{}", target: "{}") }}"#,
+                commit, target
+            ),
+        );
+        assert_eq!(&res["inferFilter"], expected);
+    }
+}
+
+#[test]
+fn filter_reduction_test() {
+    let repo = test_repo("filter_reduction_test");
+    let commit = commit_files(&repo, &[], &[("a/f", "1"), ("b/f", "234")]);
+    let empty = commit_files(&repo, &[], &[]);
+    let context = repo_context(repo);
+
+    let res = run_root(
+        &context,
+        &format!(
+            r#"query {{
+                a: filterReduction(repo: "repo", at: "{0}", spec: ":/a")
+                b: filterReduction(repo: "repo", at: "{0}", spec: ":/b")
+                empty: filterReduction(repo: "repo", at: "{1}", spec: ":/b")
+            }}"#,
+            commit, empty
+        ),
+    );
+    assert_eq!(res, json!({ "a": 0.25, "b": 0.75, "empty": 0.0 }));
+}
+
+#[test]
+fn error_extensions_test() {
+    let repo = test_repo("error_extensions_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let query = format!(
+        r#"query {{ rev(at: "{}", filter: ":/a") {{ file(path: "c") {{ path }} }} }}"#,
+        commit
+    );
+    let errors = run_errors(&context, &query);
+    let errors = serde_json::to_value(&errors).unwrap();
+    assert_eq!(
+        errors[0]["extensions"],
+        json!({ "path": "c", "filter": ":/a", "commit": commit.to_string() })
+    );
+}
+
+#[test]
+fn filter_tree_test() {
+    let repo = test_repo("filter_tree_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let (tree, expected) = {
+        let tree = repo.find_commit(commit).unwrap().tree().unwrap();
+        let subtree = tree.get_path(std::path::Path::new("a")).unwrap().id();
+        (tree.id(), subtree)
+    };
+    let context = repo_context(repo);
+
+    let query = format!(r#"query {{ filterTree(tree: "{}", spec: ":/a") }}"#, tree);
+    let res = run_root(&context, &query);
+    assert_eq!(res, json!({ "filterTree": expected.to_string() }));
+}
+
+#[test]
+fn survives_filters_test() {
+    let repo = test_repo("survives_filters_test");
+    let commit = commit_file(&repo, "a/b/c", "content");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{ file(path: "b/c") {{
+                survivesFilters(specs: [":/a/b", ":/x", ":prefix=p", "::a/b/c"]) {{
+                    spec survives path }} }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["file"]["survivesFilters"],
+        json!([
+            {"spec": ":/a/b", "survives": true, "path": "c"},
+            {"spec": ":/x", "survives": false, "path": null},
+            {"spec": ":prefix=p", "survives": true, "path": "p/a/b/c"},
+            {"spec": "::a/b/c", "survives": true, "path": "a/b/c"},
+        ])
+    );
+}
+
+#[test]
+fn filter_patch_test() {
+    let repo = test_repo("filter_patch_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[("a/f", "1\n2\n"), ("b/f", "1\n3\n"), ("b/g", "g\n")],
+    );
+    let context = repo_context(repo);
+
+    let query = format!(
+        r#"query {{ filterPatch(repo: "repo", at: "{}", from: ":/a", to: ":/b") }}"#,
+        commit
+    );
+    let res = run_root(&context, &query);
+    let patch = res["filterPatch"].clone();
+    let patch = patch.as_str().unwrap();
+    assert!(patch.starts_with("diff --git a/f b/f\n"), "{}", patch);
+    assert!(patch.contains("@@ -1,2 +1,2 @@\n 1\n-2\n+3\n"), "{}", patch);
+    assert!(
+        patch.contains("--- /dev/null\n+++ b/g\n@@ -0,0 +1 @@\n+g\n"),
+        "{}",
+        patch
+    );
+}
+
+#[test]
+fn multi_rev_test() {
+    let repo = test_repo("multi_rev_test");
+    let a = commit_file(&repo, "x/a", "a");
+    let b = commit_file(&repo, "x/b", "b");
+    repo.reference(
+        "refs/josh/upstream/a.git/refs/heads/master",
+        a,
+        false,
+        "test",
+    )
+    .unwrap();
+    repo.reference("refs/josh/upstream/b.git/refs/heads/main", b, false, "test")
+        .unwrap();
+    let context = repo_context(repo);
+
+    let res = run_root(
+        &context,
+        r#"query { multiRev(filter: ":/x", refs: [
+            { repo: "a", at: "refs/heads/master" },
+            { repo: "b", at: "refs/heads/main" },
+        ]) { files { path } } }"#,
+    );
+    assert_eq!(
+        res,
+        json!({ "multiRev": [
+            { "files": [{ "path": "a" }] },
+            { "files": [{ "path": "b" }] },
+        ] })
+    );
+}
+
+#[test]
+fn mappings_test() {
+    let repo = test_repo("mappings_test");
+    let mut history: Vec<git2::Oid> = vec![];
+    for name in ["1", "2", "3"].iter() {
+        let parents: Vec<_> = history.last().cloned().into_iter().collect();
+        history.push(commit_files(
+            &repo,
+            &parents,
+            &[(&format!("m/{}", name), name)],
+        ));
+    }
+    let other = commit_file(&repo, "m/other", "other");
+    repo.reference(
+        "refs/josh/upstream/a.git/refs/heads/master",
+        history[2],
+        false,
+        "test",
+    )
+    .unwrap();
+
+    let transaction = cache::Transaction::new(repo, None);
+    let filter = filter::parse(":/m").unwrap();
+    for id in history.iter().chain([other].iter()) {
+        let commit = transaction.repo().find_commit(*id).unwrap();
+        filter::apply_to_commit(filter, &commit, &transaction).unwrap();
+    }
+    let expected = {
+        let mut expected: Vec<_> = history
+            .iter()
+            .map(|id| {
+                (
+                    id.to_string(),
+                    transaction.get(filter, *id).unwrap().to_string(),
+                )
+            })
+            .collect();
+        expected.sort();
+        expected
+    };
+    let context = context(transaction);
+
+    let page = |after: &str| {
+        let res = run_root(
+            &context,
+            &format!(
+                r#"query {{ mappings(repo: "a", filter: ":/m", first: 2{}) {{
+                    original filtered }} }}"#,
+                after
+            ),
+        );
+        res["mappings"].clone()
+    };
+    let entry = |i: usize| json!({"original": expected[i].0, "filtered": expected[i].1});
+
+    assert_eq!(page(""), json!([entry(0), entry(1)]));
+    assert_eq!(
+        page(&format!(r#", after: "{}""#, expected[1].0)),
+        json!([entry(2)])
+    );
+    assert_eq!(page(&format!(r#", after: "{}""#, expected[2].0)), json!([]));
+
+    // Requests for the pages share the reachable commits of the tip of the repo
+    let transaction = context.transaction.lock().unwrap();
+    let reachable = transaction.reachable(&[history[2]]).unwrap();
+    assert_eq!(reachable.len(), 3);
+    assert!(std::sync::Arc::ptr_eq(
+        &reachable,
+        &transaction
+            .clone()
+            .unwrap()
+            .reachable(&[history[2], history[2]])
+            .unwrap()
+    ));
+}
+
+#[test]
+fn preview_push_test() {
+    let repo = test_repo("preview_push_test");
+    let upstream = commit_file(&repo, "a/f", "1");
+    let refname = "refs/josh/upstream/repo.git/refs/heads/master";
+    repo.reference(refname, upstream, false, "test").unwrap();
+    repo.reference_symbolic("refs/josh/upstream/repo.git/HEAD", refname, false, "test")
+        .unwrap();
+    let context = context(cache::Transaction::new(
+        repo,
+        Some("refs/josh/upstream/repo.git/"),
+    ));
+
+    let res = run_root(
+        &context,
+        r#"query { resolveRef(repo: "repo", ref: "refs/heads/master", filter: ":/a") { filteredOid } }"#,
+    );
+    let filtered = res["resolveRef"]["filteredOid"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let pushed = {
+        let transaction = context.transaction.lock().unwrap();
+        let repo = transaction.repo();
+        let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+        let parent = git2::Oid::from_str(&filtered).unwrap();
+        let c1 = commit_with(repo, &signature, "add g", &[parent], &[("g", Some("2"))]);
+        commit_with(repo, &signature, "again", &[c1], &[])
+    };
+
+    // `onto` defaults to HEAD of the repo
+    let res = run(
+        &context,
+        &format!(
+            r#"mutation {{ previewPush(filter: ":/a", from: "{}") }}"#,
+            pushed
+        ),
+    );
+    let created = res["previewPush"].clone();
+    assert_eq!(created.as_array().unwrap().len(), 2);
+
+    let transaction = context.transaction.lock().unwrap();
+    let repo = transaction.repo();
+    let first = repo
+        .find_commit(git2::Oid::from_str(created[0].as_str().unwrap()).unwrap())
+        .unwrap();
+    assert_eq!(first.summary(), Some("add g"));
+    assert_eq!(first.parent_id(0).unwrap(), upstream);
+    assert!(first
+        .tree()
+        .unwrap()
+        .get_path(std::path::Path::new("a/g"))
+        .is_ok());
+    assert_eq!(repo.refname_to_id(refname).unwrap(), upstream);
+}
+
+#[test]
+fn check_reversible_test() {
+    let repo = test_repo("check_reversible_test");
+    let upstream = commit_file(&repo, "f", "1");
+    let main = "refs/josh/upstream/repo.git/refs/heads/main";
+    repo.reference(main, upstream, false, "test").unwrap();
+    repo.reference_symbolic("refs/josh/upstream/repo.git/HEAD", main, false, "test")
+        .unwrap();
+    let good = commit_files(&repo, &[upstream], &[("f", "2")]);
+    let bad = commit_files(&repo, &[good], &[("g", "2")]);
+    repo.reference(
+        "refs/josh/upstream/repo.git/refs/heads/topic",
+        bad,
+        false,
+        "test",
+    )
+    .unwrap();
+    let context = repo_context(repo);
+    // `onto` defaults to HEAD of the repo and `from` can be one of its branches
+    let check = |from: &str| {
+        let res = run_root(
+            &context,
+            &format!(
+                r#"query {{ checkReversible(repo: "repo", filter: "::f", from: "{}") {{
+                    commit missingPaths extraPaths }} }}"#,
+                from
+            ),
+        );
+        res["checkReversible"].clone()
+    };
+
+    assert_eq!(check(&good.to_string()), json!([]));
+    assert_eq!(
+        check("refs/heads/topic"),
+        json!([{ "commit": bad.to_string(), "missingPaths": ["g"], "extraPaths": [] }])
+    );
+}
+
+#[test]
+fn resolve_ref_test() {
+    let repo = test_repo("resolve_ref_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    repo.reference(
+        "refs/josh/upstream/repo.git/refs/heads/master",
+        commit,
+        false,
+        "test",
+    )
+    .unwrap();
+    let context = repo_context(repo);
+
+    let query = r#"query { resolveRef(repo: "/repo", ref: "refs/heads/master", filter: ":/") {
+        originalOid filteredOid } }"#;
+    let res = run_root(&context, query);
+    assert_eq!(
+        res,
+        json!({ "resolveRef": {
+            "originalOid": commit.to_string(),
+            "filteredOid": commit.to_string(),
+        }})
+    );
+}
+
+#[test]
+fn set_note_test() {
+    let repo = test_repo("set_note_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"mutation {{ setNote(commit: "{}", ref: "refs/notes/ci", text: "passed") }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res, json!({ "setNote": true }));
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{ notes(ref: "refs/notes/ci") }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res, json!({ "rev": { "notes": "passed" } }));
+}
+
+#[test]
+fn default_branch_test() {
+    let repo = test_repo("default_branch_test");
+    let commit = commit_file(&repo, "a", "content");
+    repo.reference(
+        "refs/josh/upstream/repo.git/refs/heads/main",
+        commit,
+        false,
+        "test",
+    )
+    .unwrap();
+    let context = context(cache::Transaction::new(
+        repo,
+        Some("refs/josh/upstream/repo.git/"),
+    ));
+    let query = || run(&context, "query { defaultBranch }")["defaultBranch"].clone();
+
+    assert_eq!(query(), json!(null));
+    let res = run(&context, r#"mutation { setDefaultBranch(branch: "main") }"#);
+    assert_eq!(res, json!({ "setDefaultBranch": true }));
+    assert_eq!(query(), json!("refs/heads/main"));
+
+    let errors = run_errors(
+        &context,
+        r#"mutation { setDefaultBranch(branch: "refs/heads/nope") }"#,
+    );
+    assert_eq!(
+        errors[0].error().message(),
+        "JoshError(no branch \"refs/heads/nope\")"
+    );
+    assert_eq!(query(), json!("refs/heads/main"));
+}
+
+#[test]
+fn namespaced_rev_test() {
+    let repo = test_repo("namespaced_rev_test");
+    let c1 = commit_files(&repo, &[], &[("a/f", "1")]);
+    let c2 = commit_files(&repo, &[c1], &[("a/f", "2"), ("a/g", "1")]);
+    repo.reference(
+        "refs/josh/upstream/repo.git/refs/heads/old",
+        c1,
+        false,
+        "test",
+    )
+    .unwrap();
+    // Revision arguments are resolved in the upstream namespace of the repo,
+    // like the proxy does for the schema of a repo
+    let context = context(cache::Transaction::new(
+        repo,
+        Some("refs/josh/upstream/repo.git/"),
+    ));
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                changedSubset(since: "refs/heads/old") {{ files {{ path }} }}
+                rangeStats(since: "refs/heads/old") {{ commits filesChanged }}
+                reachableFrom(other: "refs/heads/old")
+                changedPaths(against: "refs/heads/old") {{ status path {{ path }} }}
+                diff(against: "refs/heads/old") {{ newPath insertions deletions }}
+            }} }}"#,
+            c2
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "changedSubset": {"files": [{"path": "f"}, {"path": "g"}]},
+            "rangeStats": {"commits": 1, "filesChanged": 2},
+            "reachableFrom": false,
+            "changedPaths": [
+                {"status": "modified", "path": {"path": "f"}},
+                {"status": "added", "path": {"path": "g"}},
+            ],
+            "diff": [
+                {"newPath": "f", "insertions": 1, "deletions": 1},
+                {"newPath": "g", "insertions": 1, "deletions": 0},
+            ],
+        })
+    );
+}
+
+#[test]
+fn reachable_from_test() {
+    let repo = test_repo("reachable_from_test");
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let c1 = commit_with(&repo, &signature, "1", &[], &[]);
+    let c2 = commit_with(&repo, &signature, "2", &[c1], &[]);
+    let other = commit_with(&repo, &signature, "3", &[c1], &[]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                a: rev(at: "{0}", filter: ":/x") {{ reachableFrom(other: "{1}") }}
+                b: rev(at: "{1}") {{ reachableFrom(other: "{1}") }}
+                c: rev(at: "{1}") {{ reachableFrom(other: "{0}") }}
+                d: rev(at: "{2}") {{ reachableFrom(other: "{1}") }}
+            }}"#,
+            c1, c2, other
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "a": {"reachableFrom": true},
+            "b": {"reachableFrom": true},
+            "c": {"reachableFrom": false},
+            "d": {"reachableFrom": false},
+        })
+    );
+}
+
+#[test]
+fn touches_filter_test() {
+    let repo = test_repo("touches_filter_test");
+    let c1 = commit_files(&repo, &[], &[("a/1", "a/1")]);
+    let c2 = commit_files(&repo, &[c1], &[("b/1", "b/1")]);
+    let c3 = commit_files(&repo, &[c2], &[("a/2", "a/2")]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                c1: rev(at: "{0}", filter: ":/a") {{ touchesFilter }}
+                c2: rev(at: "{1}", filter: ":/a") {{ touchesFilter }}
+                c3: rev(at: "{2}", filter: ":/a") {{ touchesFilter }}
+                b1: rev(at: "{0}", filter: ":/b") {{ touchesFilter }}
+            }}"#,
+            c1, c2, c3
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "c1": {"touchesFilter": true},
+            "c2": {"touchesFilter": false},
+            "c3": {"touchesFilter": true},
+            "b1": {"touchesFilter": false},
+        })
+    );
+}
+
+#[test]
+fn tree_test() {
+    let repo = test_repo("tree_test");
+    let commit = commit_file(&repo, "a/b/c", "content");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                tree {{ path dir(relative: "b") {{ path hash }} }}
+                dir {{ dir(relative: "b") {{ hash }} }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res["rev"]["tree"]["path"], json!(""));
+    assert_eq!(res["rev"]["tree"]["dir"]["path"], json!("b"));
+    assert_eq!(
+        res["rev"]["tree"]["dir"]["hash"],
+        res["rev"]["dir"]["dir"]["hash"]
+    );
+}
+
+#[test]
+fn named_filter_test() {
+    let repo = test_repo("named_filter_test");
+    let commit = commit_file(
+        &repo,
+        ".josh",
+        "# Views of this repo\n\
+         docs = :/docs\n\
+         \n\
+         api = :[\n\
+         \x20   ::src/api/\n\
+         \x20   ::proto/\n\
+         ]\n\
+         broken = :nope\n",
+    );
+    let master = "refs/josh/upstream/repo.git/refs/heads/master";
+    repo.reference(master, commit, false, "").unwrap();
+    repo.reference_symbolic("refs/josh/upstream/repo.git/HEAD", master, false, "")
+        .unwrap();
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        r#"query {
+            docs: namedFilter(name: "docs")
+            api: namedFilter(name: "api", at: "refs/heads/master")
+        }"#,
+    );
+    assert_eq!(
+        res,
+        json!({
+            "docs": ":/docs",
+            "api": filter::spec(filter::parse(":[::src/api/,::proto/]").unwrap()),
+        })
+    );
+
+    for (name, error) in [
+        ("missing", r#"JoshError(no filter "missing" in .josh)"#),
+        ("broken", ""),
+    ]
+    .iter()
+    {
+        let errors = run_errors(
+            &context,
+            &format!(r#"query {{ namedFilter(name: "{}") }}"#, name),
+        );
+        assert!(errors[0].error().message().starts_with(error));
+    }
+}
+
+#[test]
+fn changed_paths_test() {
+    let repo = test_repo("changed_paths_test");
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let c1 = commit_with(
+        &repo,
+        &signature,
+        "msg",
+        &[],
+        &[
+            ("a/f", Some("f\n")),
+            ("a/g", Some("g\n")),
+            ("a/h", Some("a longer file\nthat is renamed\n")),
+        ],
+    );
+    let c2 = commit_with(
+        &repo,
+        &signature,
+        "msg",
+        &[c1],
+        &[
+            ("a/f", Some("changed\n")),
+            ("a/g", None),
+            ("a/h", None),
+            ("a/i", Some("a longer file\nthat is renamed\n")),
+            ("a/j", Some("j\n")),
+            ("b/x", Some("x\n")),
+        ],
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                root: rev(at: "{0}", filter: ":/a") {{
+                    changedPaths {{ status path {{ path }} }} }}
+                head: rev(at: "{1}", filter: ":/a") {{
+                    changedPaths {{ status oldPath path {{ path text }} }} }}
+                same: rev(at: "{1}", filter: ":/a") {{
+                    changedPaths(against: "{1}") {{ status }} }}
+            }}"#,
+            c1, c2
+        ),
+    );
+    assert_eq!(
+        res["root"]["changedPaths"],
+        json!([
+            {"status": "added", "path": {"path": "f"}},
+            {"status": "added", "path": {"path": "g"}},
+            {"status": "added", "path": {"path": "h"}},
+        ])
+    );
+    assert_eq!(
+        res["head"]["changedPaths"],
+        json!([
+            {"status": "modified", "oldPath": null, "path": {"path": "f", "text": "changed\n"}},
+            {"status": "deleted", "oldPath": null, "path": {"path": "g", "text": "g\n"}},
+            {"status": "renamed", "oldPath": "h", "path": {
+                "path": "i", "text": "a longer file\nthat is renamed\n"}},
+            {"status": "added", "oldPath": null, "path": {"path": "j", "text": "j\n"}},
+        ])
+    );
+    assert_eq!(res["same"]["changedPaths"], json!([]));
+}
+
+#[test]
+fn diff_test() {
+    let repo = test_repo("diff_test");
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let c1 = commit_with(
+        &repo,
+        &signature,
+        "msg",
+        &[],
+        &[("a/f", Some("1\n2\n")), ("a/g", Some("g\n"))],
+    );
+    let c2 = commit_with(
+        &repo,
+        &signature,
+        "msg",
+        &[c1],
+        &[
+            ("a/f", Some("1\n3\n4\n")),
+            ("a/g", None),
+            ("b/x", Some("x\n")),
+        ],
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                c1: rev(at: "{0}", filter: ":/a") {{
+                    diff {{ oldPath newPath insertions deletions }} }}
+                c2: rev(at: "{1}", filter: ":/a") {{
+                    diff {{ oldPath newPath insertions deletions patch }} }}
+                against: rev(at: "{1}", filter: ":/a") {{
+                    diff(against: "{1}") {{ newPath }} }}
+            }}"#,
+            c1, c2
+        ),
+    );
+    assert_eq!(
+        res["c1"]["diff"],
+        json!([
+            {"oldPath": null, "newPath": "f", "insertions": 2, "deletions": 0},
+            {"oldPath": null, "newPath": "g", "insertions": 1, "deletions": 0},
+        ])
+    );
+    let diff = &res["c2"]["diff"];
+    assert_eq!(diff[0]["newPath"], json!("f"));
+    assert_eq!(diff[0]["insertions"], json!(2));
+    assert_eq!(diff[0]["deletions"], json!(1));
+    let patch = diff[0]["patch"].as_str().unwrap();
+    assert!(
+        patch.contains("@@ -1,2 +1,3 @@\n 1\n-2\n+3\n+4\n"),
+        "{}",
+        patch
+    );
+    assert_eq!(diff[1]["oldPath"], json!("g"));
+    assert_eq!(diff[1]["newPath"], json!(null));
+    assert_eq!(diff[1]["deletions"], json!(1));
+    assert_eq!(res["against"]["diff"], json!([]));
+}
+
+#[test]
+fn manifest_test() {
+    let repo = test_repo("manifest_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("a/Cargo.toml", "[package]\nname = \"a\"\n"),
+            ("a/package.json", r#"{"name": "a-js"}"#),
+            ("Cargo.toml", "[package]\nname = \"root\"\n"),
+        ],
+    );
+    let context = repo_context(repo);
+    let query = |kind: &str| {
+        execute(
+            &repo_schema("repo"),
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: ":/a") {{
+                    manifest(kind: "{}") {{ name: string(at: "/package/name") js: string(at: "/name") }} }} }}"#,
+                commit, kind
+            ),
+        )
+    };
+
+    let (res, errors) = query("cargo");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(res["rev"]["manifest"], json!({"name": "a", "js": null}));
+    let (res, errors) = query("npm");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(res["rev"]["manifest"], json!({"name": null, "js": "a-js"}));
+    assert_eq!(query("python").1.len(), 1);
+    assert_eq!(
+        query("maven").1[0].error().message(),
+        "JoshError(unknown manifest kind \"maven\")"
+    );
+}
+
+#[test]
+fn same_content_test() {
+    let repo = test_repo("same_content_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[("a/src.txt", "x"), ("a/gen.txt", "x"), ("a/other.txt", "y")],
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                same: sameContent(a: "src.txt", b: "gen.txt")
+                different: sameContent(a: "src.txt", b: "other.txt")
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res["rev"], json!({ "same": true, "different": false }));
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                sameContent(a: "src.txt", b: "missing.txt") }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn attributes_test() {
+    let repo = test_repo("attributes_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            (
+                ".gitattributes",
+                "# comment\n*.txt text eol=crlf\n[ -text\na/**/*.bin -diff\n",
+            ),
+            ("a/.gitattributes", "*.txt eol=lf\n/b/*.txt !text\n"),
+            ("a/b/c.txt", ""),
+            ("a/b/d/e.bin", ""),
+            ("a/f.txt", ""),
+        ],
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                c: file(path: "b/c.txt") {{ attributes }}
+                e: file(path: "b/d/e.bin") {{ attributes }}
+                f: file(path: "f.txt") {{ attributes }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "c": {"attributes": ["eol=lf"]},
+            "e": {"attributes": ["-diff"]},
+            "f": {"attributes": ["text", "eol=lf"]},
+        })
+    );
+}
+
+#[test]
+fn parent_count_test() {
+    let repo = test_repo("parent_count_test");
+    let base = commit_files(&repo, &[], &[("a/f", "1"), ("b/f", "1")]);
+    let a1 = commit_files(&repo, &[base], &[("a/f", "2")]);
+    let a2 = commit_files(&repo, &[base], &[("a/g", "1")]);
+    let b = commit_files(&repo, &[a1], &[("b/f", "2")]);
+    let merge = commit_files(&repo, &[a1, a2], &[("a/g", "1")]);
+    // Both parents map to the filtered `a1`, so does the merge
+    let merge_outside = commit_files(&repo, &[a1, b], &[("b/f", "2")]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                a: rev(at: "{0}") {{ parentCount isMerge }}
+                b: rev(at: "{0}", filter: ":/a") {{ parentCount isMerge }}
+                c: rev(at: "{1}") {{ parentCount isMerge }}
+                d: rev(at: "{1}", filter: ":/a") {{ parentCount isMerge }}
+            }}"#,
+            merge, merge_outside
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "a": {"parentCount": 2, "isMerge": true},
+            "b": {"parentCount": 2, "isMerge": true},
+            "c": {"parentCount": 2, "isMerge": true},
+            "d": {"parentCount": 1, "isMerge": false},
+        })
+    );
+}
+
+#[test]
+fn sorted_files_test() {
+    let repo = test_repo("sorted_files_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[("a.b", "content"), ("a/b", "content"), ("a/c/d", "content")],
+    );
+    let context = repo_context(repo);
+
+    let query = |sort: bool| {
+        run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}") {{ files(sort: {}) {{ path }} }} }}"#,
+                commit, sort
+            ),
+        )
+    };
+    assert_eq!(
+        query(false)["rev"]["files"],
+        json!([{"path": "a.b"}, {"path": "a/b"}, {"path": "a/c/d"}])
+    );
+    assert_eq!(
+        query(true)["rev"]["files"],
+        json!([{"path": "a/b"}, {"path": "a/c/d"}, {"path": "a.b"}])
+    );
+}
+
+#[test]
+fn document_float_test() {
+    let repo = test_repo("document_float_test");
+    let commit = commit_file(
+        &repo,
+        "a.toml",
+        "timeout = 1.5\nretries = 3\nname = \"x\"\n",
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a.toml") {{ toml {{
+                timeout: float(at: "/timeout")
+                retries: float(at: "/retries")
+                name: float(at: "/name", default: 2.5)
+                missing: float(at: "/missing")
+            }} }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["file"]["toml"],
+        json!({ "timeout": 1.5, "retries": 3.0, "name": 2.5, "missing": null })
+    );
+}
+
+#[test]
+fn csv_test() {
+    let repo = test_repo("csv_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            (
+                "a.csv",
+                "name,note\r\nx,\"a, \"\"b\"\"\"\r\ny,\"two\nlines\"\r\n",
+            ),
+            ("empty.csv", ""),
+            ("header.csv", "name,note\n"),
+            ("uneven.csv", "name,note\nx\n"),
+        ],
+    );
+    let context = repo_context(repo);
+    let query = |path: &str| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "{}") {{ csv {{ list {{ name: string(at: "/name") note: string(at: "/note") }} }} }} }} }}"#,
+            commit, path
+        )
+    };
+
+    let csv = |path: &str| run(&context, &query(path))["rev"]["file"]["csv"]["list"].clone();
+    assert_eq!(
+        csv("a.csv"),
+        json!([
+            { "name": "x", "note": "a, \"b\"" },
+            { "name": "y", "note": "two\nlines" },
+        ])
+    );
+    assert_eq!(csv("empty.csv"), json!([]));
+    assert_eq!(csv("header.csv"), json!([]));
+
+    let errors = run_errors(&context, &query("uneven.csv"));
+    assert_eq!(
+        errors[0].error().message(),
+        "invalid CSV in uneven.csv: CSV error: record 1 (line: 2, byte: 10): found record with 1 fields, but the previous record has 2 fields"
+    );
+}
+
+#[test]
+fn xml_test() {
+    let repo = test_repo("xml_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("pom.xml", "<project><version>1.2</version></project>"),
+            ("bad.xml", "<project>"),
+        ],
+    );
+    let context = repo_context(repo);
+    let query = |path: &str| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "{}") {{ xml {{ string(at: "/project/version") }} }} }} }}"#,
+            commit, path
+        )
+    };
+
+    let res = run(&context, &query("pom.xml"));
+    assert_eq!(res["rev"]["file"]["xml"]["string"], json!("1.2"));
+
+    let errors = run_errors(&context, &query("bad.xml"));
+    assert_eq!(
+        errors[0].error().message(),
+        "invalid XML in bad.xml: unclosed <project> at byte 9"
+    );
+}
+
+#[test]
+fn parse_xml_test() {
+    assert_eq!(
+        parse_xml(
+            r#"<?xml version="1.0"?>
+            <!DOCTYPE project [
+                <!ELEMENT project ANY>
+            ]>
+            <!-- build -->
+            <project xmlns="urn:x" xmlns:m="urn:m">
+                <version>1.0</version>
+                <name>a &amp; b</name>
+                <dep scope='test'>junit</dep>
+                <dep>serde</dep>
+                <empty/>
+                <m:code><![CDATA[<x>]]></m:code>
+                <mixed id="1">text &#65;&#x42;<b/></mixed>
+            </project>"#
+        ),
+        Ok(json!({
+            "project": {
+                "@xmlns": "urn:x",
+                "@xmlns:m": "urn:m",
+                "version": "1.0",
+                "name": "a & b",
+                "dep": [{ "@scope": "test", "#text": "junit" }, "serde"],
+                "empty": "",
+                "m:code": "<x>",
+                "mixed": { "@id": "1", "b": "", "#text": "text AB" },
+            }
+        }))
+    );
+
+    assert!(parse_xml("<a><b></a>").is_err());
+    assert!(parse_xml("<a>").is_err());
+    assert!(parse_xml("<a/><b/>").is_err());
+    assert!(parse_xml("<a/>text").is_err());
+    assert!(parse_xml("<a>&nope;</a>").is_err());
+    assert!(parse_xml("").is_err());
+
+    let nested = |depth| format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+    assert!(parse_xml(&nested(MAX_XML_DEPTH)).is_ok());
+    assert_eq!(
+        parse_xml(&nested(100_000)),
+        Err(format!(
+            "elements nested deeper than {} at byte {}",
+            MAX_XML_DEPTH,
+            3 * (MAX_XML_DEPTH + 1)
+        ))
+    );
+}
+
+#[test]
+fn document_jq_test() {
+    let repo = test_repo("document_jq_test");
+    let commit = commit_file(
+        &repo,
+        "a.json",
+        r#"{"deps": [{"name": "git2", "dev": false}, {"name": "tempdir", "dev": true}]}"#,
+    );
+    let context = repo_context(repo);
+    let query = |program: &str| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a.json") {{ json {{ jq(program: "{}") {{ string }} }} }} }} }}"#,
+            commit, program
+        )
+    };
+
+    let res = run(&context, &query(".deps[].name"));
+    assert_eq!(
+        res["rev"]["file"]["json"]["jq"],
+        json!([{ "string": "git2" }, { "string": "tempdir" }])
+    );
+    let res = run(&context, &query(".deps[] | select(.dev) | .name"));
+    assert_eq!(
+        res["rev"]["file"]["json"]["jq"],
+        json!([{ "string": "tempdir" }])
+    );
+
+    let res = run(&context, &query(".deps | map(.name | ascii_upcase) | .[]"));
+    assert_eq!(
+        res["rev"]["file"]["json"]["jq"],
+        json!([{ "string": "GIT2" }, { "string": "TEMPDIR" }])
+    );
+
+    for (program, message) in [
+        (
+            ".deps | nope",
+            r#"JoshError(jq: undefined filter "nope" at position 8)"#,
+        ),
+        (
+            ".deps | (",
+            "JoshError(jq: expected closing parenthesis at position 9)",
+        ),
+        (
+            ".deps[0].name + 1",
+            r#"JoshError(jq: cannot calculate "git2" + 1)"#,
+        ),
+    ]
+    .iter()
+    {
+        let errors = run_errors(&context, &query(program));
+        assert_eq!(errors[0].error().message(), *message);
+    }
+}
+
+#[test]
+fn document_keys_test() {
+    let repo = test_repo("document_keys_test");
+    let commit = commit_file(&repo, "a.json", r#"{"b": {"y": 1, "x": 2}, "a": [1]}"#);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a.json") {{ json {{
+                root: keys
+                b: keys(at: "/b")
+                a: keys(at: "/a")
+                missing: keys(at: "/c")
+            }} }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["file"]["json"],
+        json!({ "root": ["a", "b"], "b": ["x", "y"], "a": null, "missing": null })
+    );
+}
+
+#[test]
+fn document_bigint_test() {
+    let repo = test_repo("document_bigint_test");
+    let commit = commit_file(
+        &repo,
+        "a.json",
+        r#"{"t": 9999999999, "n": -9999999999, "u": 18446744073709551615, "f": 1.5}"#,
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a.json") {{ json {{
+                t: bigint(at: "/t")
+                n: bigint(at: "/n")
+                u: bigint(at: "/u")
+                f: bigint(at: "/f", default: "0")
+                ti: int(at: "/t")
+                ni: int(at: "/n")
+            }} }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["file"]["json"],
+        json!({
+            "t": "9999999999",
+            "n": "-9999999999",
+            "u": "18446744073709551615",
+            "f": "0",
+            "ti": i32::MAX,
+            "ni": i32::MIN,
+        })
+    );
+}
+
+#[test]
+fn max_parse_size_test() {
+    let repo = test_repo("max_parse_size_test");
+    let commit = commit_file(&repo, "a.json", r#"{"a": 1}"#);
+    let mut context = repo_context(repo);
+    let query = format!(
+        r#"query {{ rev(at: "{}") {{ file(path: "a.json") {{ json {{ n: int(at: "/a") }} }} }} }}"#,
+        commit
+    );
+
+    let res = run(&context, &query);
+    assert_eq!(res["rev"]["file"]["json"]["n"], json!(1));
+
+    context.set_max_parse_size(4);
+    let errors = run_errors(&context, &query);
+    assert_eq!(
+        errors[0].error().message(),
+        "a.json is too large to parse (8 bytes, limit is 4)"
+    );
+}
+
+#[test]
+fn text_lossy_test() {
+    let repo = test_repo("text_lossy_test");
+    let commit = {
+        let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+        let blob = repo.blob(b"a\xffb").unwrap();
+        let tree = filter::tree::empty(&repo);
+        let tree =
+            filter::tree::insert(&repo, &tree, std::path::Path::new("x"), blob, 0o0100644).unwrap();
+        repo.commit(None, &signature, &signature, "add file", &tree, &[])
+            .unwrap()
+    };
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "x") {{ text textLossy }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["file"],
+        json!({ "text": null, "textLossy": "a\u{fffd}b" })
+    );
+}
+
+#[test]
+fn texts_test() {
+    let repo = test_repo("texts_test");
+    let commit = commit_file(&repo, "b/c", "2");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                texts(paths: ["b/c", "missing", "b"]) {{ path text isBinary error }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"]["texts"],
+        json!([
+            { "path": "b/c", "text": "2", "isBinary": false, "error": null },
+            {
+                "path": "missing",
+                "text": null,
+                "isBinary": false,
+                "error": "the path 'missing' does not exist in the given tree",
+            },
+            { "path": "b", "text": null, "isBinary": false, "error": "not a blob: \"b\"" },
+        ])
+    );
+}
+
+#[test]
+fn base64_test() {
+    let repo = test_repo("base64_test");
+    let commit = commit_file(&repo, "a/icon.png", "\u{0}\u{89}PNG\r\n");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a/icon.png") {{ base64 }} }} }}"#,
+            commit
+        ),
+    );
+    let encoded = res["rev"]["file"]["base64"].as_str().unwrap();
+    assert_eq!(
+        base64::decode(encoded).unwrap(),
+        "\u{0}\u{89}PNG\r\n".as_bytes()
+    );
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ dir(path: "a") {{ base64 }} }} }}"#,
+            commit
+        ),
+    );
+    assert!(errors[0].error().message().contains("not a blob"));
+}
+
+#[test]
+fn raw_bytes_test() {
+    let repo = test_repo("raw_bytes_test");
+    let commit = commit_file(&repo, "a.bin", "\u{0}\u{7f}ab");
+    let mut context = repo_context(repo);
+    let query = format!(
+        r#"query {{ rev(at: "{}") {{ file(path: "a.bin") {{ rawBytes }} }} }}"#,
+        commit
+    );
+
+    let res = run(&context, &query);
+    assert_eq!(res["rev"]["file"]["rawBytes"], json!([0, 127, 97, 98]));
+
+    context.set_max_raw_bytes_size(3);
+    let errors = run_errors(&context, &query);
+    assert_eq!(
+        errors[0].error().message(),
+        "a.bin is too large for rawBytes (4 bytes, limit is 3)"
+    );
+}
+
+#[test]
+fn mode_test() {
+    let repo = test_repo("mode_test");
+    let commit = {
+        let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+        let blob = repo.blob(b"content").unwrap();
+        let mut tree = filter::tree::empty(&repo);
+        for (path, mode) in [
+            ("a/file", 0o0100644),
+            ("a/exe", 0o0100755),
+            ("a/link", 0o0120000),
+        ]
+        .iter()
+        {
+            tree = filter::tree::insert(&repo, &tree, std::path::Path::new(path), blob, *mode)
+                .unwrap();
+        }
+        let sub = git2::Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        let tree =
+            filter::tree::insert(&repo, &tree, std::path::Path::new("a/sub"), sub, 0o0160000)
+                .unwrap();
+        repo.commit(None, &signature, &signature, "add files", &tree, &[])
+            .unwrap()
+    };
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                file: file(path: "a/file") {{ mode }}
+                exe: file(path: "a/exe") {{ mode }}
+                link: file(path: "a/link") {{ mode }}
+                sub: dir(path: "a") {{ dir(relative: "sub") {{ mode }} }}
+                dir: dir(path: "a") {{ mode }}
+                root: dir {{ mode }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "file": { "mode": "100644" },
+            "exe": { "mode": "100755" },
+            "link": { "mode": "120000" },
+            "sub": { "dir": { "mode": "160000" } },
+            "dir": { "mode": "040000" },
+            "root": { "mode": "040000" },
+        })
+    );
+}
+
+#[test]
+fn entry_hashes_test() {
+    let repo = test_repo("entry_hashes_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[("a/x", "a/x"), ("a/b/y", "a/b/y"), ("c", "c")],
+    );
+    let (x, b) = {
+        let tree = repo.find_commit(commit).unwrap().tree().unwrap();
+        let id = |path: &str| {
+            tree.get_path(std::path::Path::new(path))
+                .unwrap()
+                .id()
+                .to_string()
+        };
+        (id("a/x"), id("a/b"))
+    };
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                a: dir(path: "a") {{ entryHashes {{ name oid kind }} }}
+                root: dir {{ entryHashes {{ name kind }} }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "a": { "entryHashes": [
+                { "name": "b", "oid": b, "kind": "tree" },
+                { "name": "x", "oid": x, "kind": "blob" },
+            ] },
+            "root": { "entryHashes": [
+                { "name": "a", "kind": "tree" },
+                { "name": "c", "kind": "blob" },
+            ] },
+        })
+    );
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "c") {{ entryHashes {{ name }} }} }} }}"#,
+            commit
+        ),
+    );
+    assert!(errors[0].error().message().contains("not a tree"));
+}
+
+#[test]
+fn blame_test() {
+    let repo = test_repo("blame_test");
+    let commit = |parents: &[git2::Oid], author: &str, content: &str| {
+        let signature = git2::Signature::now(author, &format!("{}@example.com", author)).unwrap();
+        commit_with(
+            &repo,
+            &signature,
+            "msg",
+            parents,
+            &[("a/f", Some(content)), ("b/f", Some(author))],
+        )
+    };
+    let c1 = commit(&[], "alice", "1\n2\n3\n");
+    let head = commit(&[c1], "bob", "1\ntwo\n3\n");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                hash
+                parents {{ hash }}
+                file(path: "f") {{ blame {{ line commit authorName authorEmail }} }}
+            }} }}"#,
+            head
+        ),
+    );
+    let filtered_head = &res["rev"]["hash"];
+    let filtered_parent = &res["rev"]["parents"][0]["hash"];
+    assert_eq!(
+        res["rev"]["file"]["blame"],
+        json!([
+            {"line": 1, "commit": filtered_parent,
+             "authorName": "alice", "authorEmail": "alice@example.com"},
+            {"line": 2, "commit": filtered_head,
+             "authorName": "bob", "authorEmail": "bob@example.com"},
+            {"line": 3, "commit": filtered_parent,
+             "authorName": "alice", "authorEmail": "alice@example.com"},
+        ])
+    );
+}
+
+#[test]
+fn size_test() {
+    let repo = test_repo("size_test");
+    let commit = commit_file(&repo, "a/b.bin", "\u{0}\u{ff}ab");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ file(path: "a/b.bin") {{ size }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res["rev"]["file"]["size"], json!(5.0));
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ dir(path: "a") {{ size }} }} }}"#,
+            commit
+        ),
+    );
+    assert!(errors[0].error().message().contains("not a blob"));
+}
+
+#[test]
+fn strict_files_test() {
+    let repo = test_repo("strict_files_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+    let query = |args: &str| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ files({}) {{ path }} dirs({}) {{ path }} }} }}"#,
+            commit, args, args
+        )
+    };
+
+    let res = run(&context, &query(r#"at: "nope", strict: false"#));
+    assert_eq!(res["rev"]["files"], json!([]));
+    assert_eq!(res["rev"]["dirs"], json!([]));
+
+    let res = run(&context, &query(r#"at: "a/b", strict: false"#));
+    assert_eq!(res["rev"]["files"], json!([]));
+
+    for args in [r#"at: "nope""#, r#"at: "a/b", strict: true"#].iter() {
+        let errors = run_errors(&context, &query(args));
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].error().message().contains("not a tree"));
+    }
+}
+
+#[test]
+fn glob_test() {
+    let repo = test_repo("glob_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("src/a.rs", "src/a.rs"),
+            ("src/m/c.rs", "src/m/c.rs"),
+            ("src/m/n/d.txt", "src/m/n/d.txt"),
+            ("e.rs", "e.rs"),
+        ],
+    );
+    let context = repo_context(repo);
+    let glob = |filter: &str, pattern: &str| {
+        run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: "{}") {{ glob(pattern: "{}") }} }}"#,
+                commit, filter, pattern
+            ),
+        )["rev"]["glob"]
+            .clone()
+    };
+
+    assert_eq!(
+        glob(":/", "**/*.rs"),
+        json!(["e.rs", "src/a.rs", "src/m/c.rs"])
+    );
+    assert_eq!(glob(":/", "src/m*"), json!(["src/m"]));
+    assert_eq!(glob(":/", "src/**/n"), json!(["src/m/n"]));
+    assert_eq!(glob(":/src", "*"), json!(["a.rs", "m"]));
+}
+
+#[test]
+fn files_glob_test() {
+    let repo = test_repo("files_glob_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("src/a.rs", "src/a.rs"),
+            ("src/b.txt", "src/b.txt"),
+            ("src/m/c.rs", "src/m/c.rs"),
+            ("src/m/n/d.rs", "src/m/n/d.rs"),
+            ("e.rs", "e.rs"),
+        ],
+    );
+    let context = repo_context(repo);
+    let query = |args: &str| {
+        format!(
+            r#"query {{ rev(at: "{}") {{ files({}, sort: true) {{ path }} }} }}"#,
+            commit, args
+        )
+    };
+    let paths = |args: &str| run(&context, &query(args))["rev"]["files"].clone();
+
+    assert_eq!(
+        paths(r#"at: "src", glob: "*.rs""#),
+        json!([{"path": "src/a.rs"}])
+    );
+    assert_eq!(
+        paths(r#"at: "src", glob: "**/*.rs""#),
+        json!([{"path": "src/a.rs"}, {"path": "src/m/c.rs"}, {"path": "src/m/n/d.rs"}])
+    );
+    assert_eq!(
+        paths(r#"at: "src", glob: "**/*.rs", depth: 2"#),
+        json!([{"path": "src/a.rs"}, {"path": "src/m/c.rs"}])
+    );
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ dirs(glob: "src/*") {{ path }} }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res["rev"]["dirs"], json!([{"path": "src/m"}]));
+
+    let errors = run_errors(&context, &query(r#"glob: "a**b""#));
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].error().message().contains("invalid glob"));
+}
+
+#[test]
+fn files_pagination_test() {
+    let repo = test_repo("files_pagination_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("a/x", "a/x"),
+            ("b", "b"),
+            ("a.b", "a.b"),
+            ("c/d/e", "c/d/e"),
+            ("c/f", "c/f"),
+        ],
+    );
+    let context = repo_context(repo);
+    let page = |args: &str| {
+        let res = run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{0}") {{
+                    filesPage({1}) {{ items {{ path }} hasMore }}
+                    dirsPage({1}) {{ items {{ path }} hasMore }}
+                }} }}"#,
+                commit, args
+            ),
+        );
+        res["rev"].clone()
+    };
+    let paths = |paths: &[&str]| -> serde_json::Value {
+        paths.iter().map(|path| json!({ "path": path })).collect()
+    };
+
+    let res = page("first: 2");
+    assert_eq!(
+        res["filesPage"],
+        json!({"items": paths(&["a/x", "a.b"]), "hasMore": true})
+    );
+    assert_eq!(
+        res["dirsPage"],
+        json!({"items": paths(&["a", "c"]), "hasMore": true})
+    );
+
+    let res = page(r#"first: 2, after: "a.b""#);
+    assert_eq!(
+        res["filesPage"],
+        json!({"items": paths(&["b", "c/d/e"]), "hasMore": true})
+    );
+
+    let res = page(r#"first: 2, after: "c/d/e""#);
+    assert_eq!(
+        res["filesPage"],
+        json!({"items": paths(&["c/f"]), "hasMore": false})
+    );
+    assert_eq!(
+        res["dirsPage"],
+        json!({"items": paths(&[]), "hasMore": false})
+    );
+
+    let res = page(r#"after: "c""#);
+    assert_eq!(
+        res["dirsPage"],
+        json!({"items": paths(&["c/d"]), "hasMore": false})
+    );
+}
+
+#[test]
+fn files_at_test() {
+    let repo = test_repo("files_at_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("x", "content"),
+            ("a/y", "content"),
+            ("a/b/c.txt", "content"),
+            ("a/b/d/e.txt", "content"),
+        ],
+    );
+    let context = repo_context(repo);
+
+    let query = |args: &str| {
+        let res = run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}") {{ files({}) {{ path }} dirs({}) {{ path }} }} }}"#,
+                commit, args, args
+            ),
+        );
+        res["rev"].clone()
+    };
+
+    let all_files = json!([
+        {"path": "a/b/c.txt"},
+        {"path": "a/b/d/e.txt"},
+        {"path": "a/y"},
+        {"path": "x"},
+    ]);
+    assert_eq!(query("sort: true")["files"], all_files);
+    assert_eq!(query(r#"at: "", sort: true"#)["files"], all_files);
+
+    assert_eq!(
+        query(r#"at: "a/b", sort: true"#),
+        json!({
+            "files": [{"path": "a/b/c.txt"}, {"path": "a/b/d/e.txt"}],
+            "dirs": [{"path": "a/b/d"}],
+        })
+    );
+    assert_eq!(
+        query(r#"at: "a", depth: 1, sort: true"#),
+        json!({
+            "files": [{"path": "a/y"}],
+            "dirs": [{"path": "a/b"}],
+        })
+    );
+}
+
+#[test]
+fn filtered_test() {
+    let repo = test_repo("filtered_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                a: rev(at: "{0}") {{ filtered }}
+                b: rev(at: "{0}", filter: ":prefix=x:/x") {{ filtered }}
+                c: rev(at: "{0}", filter: ":/a") {{ filtered }}
+            }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "a": {"filtered": false},
+            "b": {"filtered": false},
+            "c": {"filtered": true},
+        })
+    );
+}
+
+#[test]
+fn parents_test() {
+    let repo = test_repo("parents_test");
+    let base = commit_files(&repo, &[], &[("a/f", "1")]);
+    let b1 = commit_files(&repo, &[base], &[("a/f", "2")]);
+    // Only touches files outside of `a`, so maps to the same commit as `b1`
+    let c = commit_files(&repo, &[b1], &[("b/y", "1")]);
+    let b2 = commit_files(&repo, &[base], &[("a/g", "1")]);
+    let merge = commit_files(&repo, &[c, b2], &[("a/g", "1")]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{ parents {{ rev {{ hash }} }} }} }}"#,
+            merge
+        ),
+    );
+    assert_eq!(
+        res["rev"]["parents"],
+        json!([
+            {"rev": {"hash": b1.to_string()}},
+            {"rev": {"hash": b2.to_string()}},
+        ])
+    );
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                parents(limit: 1) {{ rev {{ hash }} }} }} }}"#,
+            merge
+        ),
+    );
+    assert_eq!(
+        res["rev"]["parents"],
+        json!([{"rev": {"hash": b1.to_string()}}])
+    );
+}
+
+#[test]
+fn range_stats_test() {
+    let repo = test_repo("range_stats_test");
+    let base = commit_files(&repo, &[], &[("a/f", "1\n2\n")]);
+    let c1 = commit_files(&repo, &[base], &[("a/f", "1\n3\n4\n")]);
+    let c2 = commit_files(&repo, &[c1], &[("b/f", "outside\n")]);
+    let head = commit_files(&repo, &[c2], &[("a/f", "1\n3\n"), ("a/g", "new\n")]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{ rangeStats(since: "{}") {{
+                commits filesChanged insertions deletions }} }} }}"#,
+            head, base
+        ),
+    );
+    assert_eq!(
+        res["rev"]["rangeStats"],
+        json!({"commits": 2, "filesChanged": 2, "insertions": 3, "deletions": 2})
+    );
+}
+
+#[test]
+fn entry_count_test() {
+    let repo = test_repo("entry_count_test");
+    let commit = commit_files(
+        &repo,
+        &[],
+        &[
+            ("a/b/c", "content"),
+            ("a/b/d", "content"),
+            ("a/e", "content"),
+            ("f", "content"),
+        ],
+    );
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                root: entryCount
+                all: entryCount(recursive: true)
+                a: entryCount(at: "a")
+                allA: entryCount(at: "a", recursive: true)
+                filtered: rev(filter: ":/a/b") {{ entryCount }}
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "root": 2,
+            "all": 6,
+            "a": 2,
+            "allA": 4,
+            "filtered": { "entryCount": 2 },
+        })
+    );
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ entryCount(at: "f") }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(errors[0].error().message(), r#"JoshError(not a tree: "f")"#);
+}
+
+#[test]
+fn commit_count_test() {
+    let repo = test_repo("commit_count_test");
+    let mut head: Option<git2::Oid> = None;
+    for path in ["a/f", "b/f", "a/g", "b/g", "b/h"].iter() {
+        let parents: Vec<_> = head.into_iter().collect();
+        head = Some(commit_files(&repo, &parents, &[(path, path)]));
+    }
+    let head = head.unwrap();
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                all: rev(at: "{0}") {{ commitCount }}
+                a: rev(at: "{0}", filter: ":/a") {{ commitCount }}
+                limited: rev(at: "{0}") {{ commitCount(limit: 3) }}
+                none: rev(at: "{0}", filter: ":/c") {{ commitCount }}
+            }}"#,
+            head
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "all": { "commitCount": 5 },
+            "a": { "commitCount": 2 },
+            "limited": { "commitCount": 3 },
+            "none": { "commitCount": 0 },
+        })
+    );
+}
+
+#[test]
+fn history_matching_test() {
+    let repo = test_repo("history_matching_test");
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let commit = |parents: &[git2::Oid], path: &str, message: &str| {
+        commit_with(
+            &repo,
+            &signature,
+            message,
+            parents,
+            &[(path, Some(message))],
+        )
+    };
+    let c1 = commit(&[], "a/f", "JIRA-123 add f");
+    let c2 = commit(&[c1], "b/f", "JIRA-124 add b");
+    let c3 = commit(&[c2], "a/g", "Fix JIRA-123 again");
+    let head = commit(&[c3], "a/f", "Unrelated");
+    let context = repo_context(repo);
+    let matching = |filter: &str, args: &str| {
+        run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: "{}") {{
+                    historyMatching({}) {{ rev {{ hash }} }} }} }}"#,
+                head, filter, args
+            ),
+        )["rev"]["historyMatching"]
+            .clone()
+    };
+    let hashes = |ids: &[git2::Oid]| {
+        json!(ids
+            .iter()
+            .map(|id| json!({"rev": {"hash": id.to_string()}}))
+            .collect::<Vec<_>>())
+    };
+
+    assert_eq!(matching(":/a", r#"pattern: "JIRA-123""#), hashes(&[c3, c1]));
+    assert_eq!(
+        matching(":/a", r#"pattern: "JIRA-123", limit: 1"#),
+        hashes(&[c3])
+    );
+    assert_eq!(matching(":/a", r#"pattern: "JIRA-124""#), hashes(&[]));
+    assert_eq!(matching(":/", r#"pattern: "JIRA-124""#), hashes(&[c2]));
+}
+
+#[test]
+fn age_test() {
+    let repo = test_repo("age_test");
+    let time = git2::Time::new(1600000000, 120);
+    let signature = git2::Signature::new("Josh", "josh@example.com", &time).unwrap();
+    let commit = commit_with(&repo, &signature, "empty", &[], &[]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                later: age(from: "2020-09-16T12:26:40Z")
+                earlier: age(from: "2020-09-13T13:26:40+02:00")
+                same: age(from: "2020-09-13T12:26:45Z")
+                now: age
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(res["rev"]["later"], json!("3 days ago"));
+    assert_eq!(res["rev"]["earlier"], json!("in an hour"));
+    assert_eq!(res["rev"]["same"], json!("now"));
+    assert!(res["rev"]["now"].as_str().unwrap().ends_with(" ago"));
+}
+
+#[test]
+fn cancel_test() {
+    let repo = test_repo("cancel_test");
+    let path = repo.path().to_owned();
+    let mut commits: Vec<git2::Oid> = vec![];
+    for i in 0..5000 {
+        let parents: Vec<_> = commits.last().cloned().into_iter().collect();
+        commits.push(commit_files(&repo, &parents, &[("a/f", &i.to_string())]));
+    }
+    let head = *commits.last().unwrap();
+    let transaction = cache::Transaction::new(repo, None);
+    let cancel = cache::CancelOnDrop::new(&transaction);
+    let context = std::sync::Arc::new(context(transaction));
+
+    // Like josh-proxy, execute the request on another thread
+    let request = {
+        let context = context.clone();
+        std::thread::spawn(move || {
+            let errors = run_errors(
+                &context,
+                &format!(
+                    r#"query {{ rev(at: "{}", filter: ":/a") {{ hash }} }}"#,
+                    head
+                ),
+            );
+            errors
+                .iter()
+                .map(|e| e.error().message().to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Every filtered commit is new, so all of them end up in the persistent cache
+    // and the progress of the walk is visible to other transactions
+    let filter = filter::parse(":/a").unwrap();
+    let observer = cache::Transaction::open(&path, None).unwrap();
+    let start = std::time::Instant::now();
+    while !observer.known(filter, commits[10]) {
+        assert!(start.elapsed() < std::time::Duration::from_secs(60));
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    drop(cancel);
+
+    assert_eq!(request.join().unwrap(), vec!["JoshError(cancelled)"]);
+    assert!(!observer.known(filter, head));
+}
+
+#[test]
+fn committed_test() {
+    let repo = test_repo("committed_test");
+    let time = git2::Time::new(1600000000, 120);
+    let signature = git2::Signature::new("Josh", "josh@example.com", &time).unwrap();
+    let commit = commit_with(&repo, &signature, "empty", &[], &[]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                a: committedAfter(time: "2020-09-13T14:26:39+02:00")
+                b: committedAfter(time: "2020-09-13T12:26:40Z")
+                c: committedBefore(time: "2020-09-13T12:26:41Z")
+                d: committedBefore(time: "2020-09-13T14:26:40+02:00")
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({"a": true, "b": false, "c": true, "d": false})
+    );
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ committedAfter(time: "yesterday") }} }}"#,
+            commit
+        ),
+    );
+    assert!(errors[0]
+        .error()
+        .message()
+        .starts_with(r#"JoshError(invalid time "yesterday""#));
+}
+
+#[test]
+fn message_test() {
+    let repo = test_repo("message_test");
+    let message = "Add a/b\n\nFirst paragraph\nof the body.\n\nSecond paragraph.\n\n\
+                   Signed-off-by: Josh <josh@example.com>\n";
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let files = [("a/b", Some("content"))];
+    let commit = commit_with(&repo, &signature, message, &[], &files);
+    let empty = commit_with(&repo, &signature, "", &[], &files);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{
+                full: rev(at: "{}", filter: ":/a") {{ summary message body }}
+                empty: rev(at: "{}") {{ summary message body }}
+            }}"#,
+            commit, empty
+        ),
+    );
+    assert_eq!(
+        res,
+        json!({
+            "full": {
+                "summary": "Add a/b",
+                "message": message,
+                "body": "First paragraph\nof the body.\n\nSecond paragraph.\n\n\
+                         Signed-off-by: Josh <josh@example.com>\n",
+            },
+            "empty": { "summary": "", "message": "", "body": "" },
+        })
+    );
+}
+
+#[test]
+fn trailers_test() {
+    let repo = test_repo("trailers_test");
+    let signature = git2::Signature::now("Josh", "josh@example.com").unwrap();
+    let commits: Vec<git2::Oid> = vec![
+        "Fix a\n\nBody: not a trailer\nsince this line is prose.\n",
+        "Fix b\n\nBody.\n\nReviewed-by: A <a@example.com>\n\
+         Change-Id: I123\n  continued\n",
+        "Change-Id: I456\n",
+        "",
+    ]
+    .into_iter()
+    .map(|message| commit_with(&repo, &signature, message, &[], &[]))
+    .collect();
+    let context = repo_context(repo);
+
+    let query = commits
+        .iter()
+        .enumerate()
+        .map(|(i, commit)| {
+            format!(
+                r#"c{}: rev(at: "{}") {{ trailers {{ key value }} }}"#,
+                i, commit
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let res = run(&context, &format!("query {{ {} }}", query));
+    assert_eq!(
+        res,
+        json!({
+            "c0": { "trailers": [] },
+            "c1": { "trailers": [
+                { "key": "Reviewed-by", "value": "A <a@example.com>" },
+                { "key": "Change-Id", "value": "I123 continued" },
+            ] },
+            "c2": { "trailers": [] },
+            "c3": { "trailers": [] },
+        })
+    );
+}
+
+#[test]
+fn identity_test() {
+    let repo = test_repo("identity_test");
+    let commit = {
+        let author = git2::Signature::now("Author", "author@example.com").unwrap();
+        let committer = git2::Signature::now("Committer", "committer@example.com").unwrap();
+        let tree = repo
+            .find_commit(commit_file(&repo, "a/b", "content"))
+            .unwrap()
+            .tree()
+            .unwrap();
+        repo.commit(None, &author, &committer, "add a/b", &tree, &[])
+            .unwrap()
+    };
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                authorName authorEmail committerName committerEmail }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "authorName": "Author",
+            "authorEmail": "author@example.com",
+            "committerName": "Committer",
+            "committerEmail": "committer@example.com",
+        })
+    );
+}
+
+#[test]
+fn date_test() {
+    let repo = test_repo("date_test");
+    let time = git2::Time::new(1600000000, 120);
+    let signature = git2::Signature::new("Josh", "josh@example.com", &time).unwrap();
+    let commit = commit_with(&repo, &signature, "empty", &[], &[]);
+    // Out of the range chrono can represent
+    let invalid = repo
+        .odb()
+        .unwrap()
+        .write(
+            git2::ObjectType::Commit,
+            format!(
+                "tree {}\nauthor Josh <josh@example.com> 9999999999999999 +0000\n\
+                 committer Josh <josh@example.com> 9999999999999999 +0000\n\nmsg\n",
+                filter::tree::empty_id()
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{
+                local: date(format: "%Y-%m-%d %H:%M %z")
+                colon: date(format: "%:z")
+                utc: date(format: "%Y-%m-%d %H:%M %z", utc: true)
+            }} }}"#,
+            commit
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "local": "2020-09-13 14:26 +0200",
+            "colon": "+02:00",
+            "utc": "2020-09-13 12:26 +0000",
+        })
+    );
+
+    let errors = run_errors(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}") {{ date(format: "%Y") }} }}"#,
+            invalid
+        ),
+    );
+    assert_eq!(
+        errors[0].error().message(),
+        "JoshError(invalid commit time)"
+    );
+}
+
+#[test]
+fn last_modified_test() {
+    let repo = test_repo("last_modified_test");
+    let c1 = commit_files(&repo, &[], &[("a/f", "1")]);
+    let c2 = commit_files(&repo, &[c1], &[("a/g", "1")]);
+    let c3 = commit_files(&repo, &[c2], &[("b/f", "1")]);
+    let head = commit_files(&repo, &[c3], &[("a/f", "1")]);
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        &format!(
+            r#"query {{ rev(at: "{}", filter: ":/a") {{
+                f: file(path: "f") {{ lastModified {{ rev {{ hash }} }} }}
+                g: file(path: "g") {{ lastModified {{ rev {{ hash }} }} }}
+            }} }}"#,
+            head
+        ),
+    );
+    assert_eq!(
+        res["rev"],
+        json!({
+            "f": {"lastModified": {"rev": {"hash": c1.to_string()}}},
+            "g": {"lastModified": {"rev": {"hash": c2.to_string()}}},
+        })
+    );
+}
+
+#[test]
+fn bounded_warnings_test() {
+    let repo = test_repo("bounded_warnings_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let query = |timeout: i32| {
+        run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: ":[::a/b,::x]") {{
+                    boundedWarnings(timeoutMs: {}) {{ warnings {{ message }} truncated }} }} }}"#,
+                commit, timeout
+            ),
+        )["rev"]["boundedWarnings"]
+            .clone()
+    };
+
+    assert_eq!(query(0), json!({"warnings": [], "truncated": true}));
+    assert_eq!(
+        query(10000),
+        json!({
+            "warnings": [{"message": "No match for \"::x\""}],
+            "truncated": false,
+        })
+    );
+}
+
+#[test]
+fn branch_warnings_test() {
+    let repo = test_repo("branch_warnings_test");
+    let with_x = commit_file(&repo, "x", "content");
+    let without_x = commit_file(&repo, "y", "content");
+    for (name, id) in [("master", with_x), ("other", without_x)].iter() {
+        repo.reference(
+            &format!("refs/josh/upstream/repo.git/refs/heads/{}", name),
+            *id,
+            false,
+            "test",
+        )
+        .unwrap();
+    }
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        r#"query { warnings(filter: ":[::x,::y]") { branch warnings { message } } }"#,
+    );
+    assert_eq!(
+        res["warnings"],
+        json!([
+            {"branch": "refs/heads/master", "warnings": [{"message": "No match for \"::y\""}]},
+            {"branch": "refs/heads/other", "warnings": [{"message": "No match for \"::x\""}]},
+        ])
+    );
+}
+
+#[test]
+fn namespace_test() {
+    let repo = test_repo("namespace_test");
+    let commit = commit_file(&repo, "a", "content");
+    repo.reference(
+        "refs/josh/upstream/org%2Frepo.git/refs/heads/master",
+        commit,
+        false,
+        "test",
+    )
+    .unwrap();
+    let context = repo_context(repo);
+
+    let res = run_root(&context, "query { repos { name namespace } }");
+    assert_eq!(
+        res,
+        json!({ "repos": [{ "name": "org/repo", "namespace": "org%2Frepo" }] })
+    );
+}
+
+#[test]
+fn recent_commits_test() {
+    let repo = test_repo("recent_commits_test");
+    let commit = |parents: &[git2::Oid], path: &str, time: i64| {
+        let signature =
+            git2::Signature::new("Josh", "josh@example.com", &git2::Time::new(time, 0)).unwrap();
+        commit_with(&repo, &signature, "msg", parents, &[(path, Some(path))])
+    };
+    let base = commit(&[], "a/base", 100);
+    let a1 = commit(&[base], "a/1", 200);
+    let b1 = commit(&[base], "a/2", 300);
+    let outside = commit(&[a1], "b/1", 400);
+    let a2 = commit(&[outside], "a/3", 500);
+    for (name, id) in [("master", a2), ("other", b1), ("same", b1)].iter() {
+        repo.reference(
+            &format!("refs/josh/upstream/repo.git/refs/heads/{}", name),
+            *id,
+            false,
+            "test",
+        )
+        .unwrap();
+    }
+    let context = repo_context(repo);
+
+    let res = run(
+        &context,
+        r#"query { recentCommits(limit: 3, filter: ":/a") { rev { hash } } }"#,
+    );
+    assert_eq!(
+        res["recentCommits"],
+        json!([
+            {"rev": {"hash": a2.to_string()}},
+            {"rev": {"hash": b1.to_string()}},
+            {"rev": {"hash": a1.to_string()}},
+        ])
+    );
+}
+
+#[test]
+fn ref_prefixes_test() {
+    let repo = test_repo("ref_prefixes_test");
+    let commit = commit_file(&repo, "a", "content");
+    for name in ["feature/a", "feature/b", "release/1", "master"].iter() {
+        repo.reference(
+            &format!("refs/josh/upstream/repo.git/refs/heads/{}", name),
+            commit,
+            false,
+            "test",
+        )
+        .unwrap();
+    }
+    let context = repo_context(repo);
+
+    let prefixes = |under: &str| {
+        run(
+            &context,
+            &format!(r#"query {{ refPrefixes(under: "{}") }}"#, under),
+        )["refPrefixes"]
+            .clone()
+    };
+    assert_eq!(prefixes(""), json!(["feature", "master", "release"]));
+    assert_eq!(prefixes("feature"), json!(["a", "b"]));
+    assert_eq!(prefixes("feature/"), json!(["a", "b"]));
+    assert_eq!(prefixes("nope"), json!([]));
+}
+
+#[test]
+fn marker_count_test() {
+    let repo = test_repo("marker_count_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    let count = || {
+        run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}", filter: ":/a") {{
+                    file(path: "b") {{ meta(topic: "t") {{ count }} }} }} }}"#,
+                commit
+            ),
+        )["rev"]["file"]["meta"]["count"]
+            .clone()
+    };
+    let add = |data: &str| {
+        run(
+            &context,
+            &format!(
+                r#"mutation {{ meta(commit: "{}", topic: "t", add: [{{ path: "a/b", data: ["{}"] }}]) }}"#,
+                commit, data
+            ),
+        );
+    };
+
+    assert_eq!(count(), json!(0));
+    add("{}");
+    assert_eq!(count(), json!(1));
+    assert_eq!(count(), json!(1));
+    add("[]");
+    assert_eq!(count(), json!(2));
+
+    // Counts are cached across transactions
+    let transaction = context.transaction.lock().unwrap();
+    let key = (
+        git2::Oid::zero(),
+        commit,
+        filter::nop(),
+        std::path::PathBuf::from("b"),
+        "t".to_string(),
+    );
+    transaction.insert_marker_count(key.clone(), 3);
+    assert_eq!(transaction.get_marker_count(&key), Some(3));
+    assert_eq!(transaction.clone().unwrap().get_marker_count(&key), Some(3));
+}
+
+#[test]
+fn marker_topic_test() {
+    let repo = test_repo("marker_topic_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let context = repo_context(repo);
+
+    for topic in ["../x~", ".."].iter() {
+        run(
+            &context,
+            &format!(
+                r#"mutation {{ meta(commit: "{}", topic: "{}", add: [{{ path: "a/b", data: ["{{\"k\": \"v\"}}"] }}]) }}"#,
+                commit, topic
+            ),
+        );
+        let res = run(
+            &context,
+            &format!(
+                r#"query {{ rev(at: "{}") {{ file(path: "a/b") {{ meta(topic: "{}") {{ count data {{ string(at: "/k") }} }} }} }} }}"#,
+                commit, topic
+            ),
+        );
+        assert_eq!(
+            res["rev"]["file"]["meta"],
+            json!({ "count": 1, "data": [{ "string": "v" }] })
+        );
+    }
+
+    let transaction = context.transaction.lock().unwrap();
+    let tree = transaction
+        .repo()
+        .revparse_single("refs/josh/meta")
+        .unwrap()
+        .peel_to_tree()
+        .unwrap();
+    let mut names: Vec<_> = tree.iter().map(|e| e.name().unwrap().to_string()).collect();
+    names.sort();
+    assert_eq!(names, vec!["%2E%2E", "..%2Fx%7E"]);
+    assert_eq!(escape_topic("review"), "review");
+    assert_eq!(escape_topic("a%2F"), "a%252F");
+}
+
+#[test]
+fn entry_cache_test() {
+    let repo = test_repo("entry_cache_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    let blob = repo.blob(b"content").unwrap();
+    let mut context = repo_context(repo);
+    let query = format!(
+        r#"query {{ rev(at: "{}") {{ files {{ hash text }} }} }}"#,
+        commit
+    );
+    let expected = json!([{"hash": blob.to_string(), "text": "content"}]);
+
+    for enabled in [true, false].iter() {
+        context.set_entry_cache(*enabled);
+        for _ in 0..2 {
+            let res = run(&context, &query);
+            assert_eq!(res["rev"]["files"], expected);
+        }
+        assert_eq!(context.entries.is_some(), *enabled);
+    }
+}
+
+#[test]
+fn walk_paths_parallel_test() {
+    let repo = test_repo("walk_paths_parallel_test");
+    let blob = repo.blob(b"content").unwrap();
+    let mut tree = filter::tree::empty(&repo);
+    for path in ["a", "b/c", "b/d/e", "f/g/h/i", "j"].iter() {
+        tree = filter::tree::insert(&repo, &tree, std::path::Path::new(path), blob, 0o0100644)
+            .unwrap();
+    }
+
+    for kind in [git2::ObjectType::Blob, git2::ObjectType::Tree].iter() {
+        for depth in [None, Some(0), Some(1), Some(2)].iter() {
+            assert_eq!(
+                walk_paths_parallel(&repo, &tree, *depth, *kind).unwrap(),
+                walk_paths(&tree, std::path::Path::new(""), *depth, *kind).unwrap()
+            );
+        }
+    }
+
+    // Trees that only exist in memory are not visible to the worker threads
+    let odb = repo.odb().unwrap();
+    let _mempack = odb.add_new_mempack_backend(1000).unwrap();
+    let blob = repo.blob(b"in memory").unwrap();
+    let tree =
+        filter::tree::insert(&repo, &tree, std::path::Path::new("k/l"), blob, 0o0100644).unwrap();
+    let tree = repo.find_tree(tree.id()).unwrap();
+    assert!(git2::Repository::open(repo.path())
+        .unwrap()
+        .find_tree(tree.id())
+        .is_err());
+    assert_eq!(
+        walk_paths_parallel(&repo, &tree, None, git2::ObjectType::Blob).unwrap(),
+        walk_paths(
+            &tree,
+            std::path::Path::new(""),
+            None,
+            git2::ObjectType::Blob
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[ignore]
+fn walk_paths_parallel_bench() {
+    // cargo test --release walk_paths_parallel_bench -- --ignored --nocapture
+    let repo = test_repo("walk_paths_parallel_bench");
+    let blob = repo.blob(b"content").unwrap();
+    let mut root = repo.treebuilder(None).unwrap();
+    for i in 0..100 {
+        let mut dir = repo.treebuilder(None).unwrap();
+        for j in 0..1000 {
+            dir.insert(format!("file{}", j), blob, 0o0100644).unwrap();
+        }
+        root.insert(format!("dir{}", i), dir.write().unwrap(), 0o0040000)
+            .unwrap();
+    }
+    let tree = repo.find_tree(root.write().unwrap()).unwrap();
+    assert!(is_large_tree(&repo, &tree) || num_cpus::get() < 2);
+
+    let start = std::time::Instant::now();
+    let serial = walk_paths(
+        &tree,
+        std::path::Path::new(""),
+        None,
+        git2::ObjectType::Blob,
+    )
+    .unwrap();
+    let serial_time = start.elapsed();
+    let start = std::time::Instant::now();
+    let parallel = walk_paths_parallel(&repo, &tree, None, git2::ObjectType::Blob).unwrap();
+    let parallel_time = start.elapsed();
+
+    assert_eq!(serial.len(), 100_000);
+    assert_eq!(serial, parallel);
+    println!(
+        "{} paths: serial {:?}, parallel {:?} on {} cpus",
+        serial.len(),
+        serial_time,
+        parallel_time,
+        num_cpus::get()
+    );
+}
+
+#[test]
+fn rev_snapshot_test() {
+    let repo = test_repo("rev_snapshot_test");
+    let first = commit_file(&repo, "a", "1");
+    let second = commit_file(&repo, "a", "2");
+    let refname = "refs/josh/upstream/repo.git/refs/heads/master";
+    repo.reference(refname, first, false, "test").unwrap();
+    let query = r#"query { rev(at: "refs/heads/master") { hash } }"#;
+
+    let snapshot = repo_context(repo);
+    let res = run(&snapshot, query);
+    assert_eq!(res["rev"]["hash"], json!(first.to_string()));
+
+    // A fetch updating the ref while the context is in use
+    let transaction = snapshot.transaction.lock().unwrap();
+    transaction
+        .repo()
+        .reference(refname, second, true, "test")
+        .unwrap();
+    let fresh = context(transaction.clone().unwrap());
+    drop(transaction);
+
+    let res = run(&snapshot, query);
+    assert_eq!(res["rev"]["hash"], json!(first.to_string()));
+    let res = run(&fresh, query);
+    assert_eq!(res["rev"]["hash"], json!(second.to_string()));
+}
+
+#[test]
+fn query_ref_test() {
+    let repo = test_repo("query_ref_test");
+    let commit = commit_file(&repo, "a/b", "content");
+    repo.reference(
+        "refs/josh/upstream/repo.git/refs/heads/master",
+        commit,
+        false,
+        "test",
+    )
+    .unwrap();
+    let context = repo_context(repo);
+
+    let query = |refname: &str| {
+        execute(
+            &schema(),
+            &context,
+            &format!(
+                r#"query {{ ref(repo: "/repo", refname: "{}") {{ name rev {{ hash }} }} }}"#,
+                refname
+            ),
+        )
+    };
+
+    let (res, errors) = query("refs/heads/master");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        res,
+        json!({ "ref": { "name": "refs/heads/master", "rev": { "hash": commit.to_string() } } })
+    );
+
+    let (_, errors) = query("refs/heads/missing");
+    assert_eq!(
+        errors[0].error().message(),
+        r#"JoshError(no ref "refs/heads/missing" in "/repo")"#
+    );
+}
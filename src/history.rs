@@ -1,27 +1,38 @@
 use super::*;
 
+#[derive(Debug, PartialEq)]
+pub enum WalkResult {
+    Done,
+    /// The cancel token of the transaction was set, commits filtered so far stay cached
+    Cancelled,
+}
+
+/// Filter all commits reachable from `input` that are not yet known.
+/// With `hide` the walk stops at that commit, its filtered version has to be
+/// known already (or is trusted to be computed later) for the result to be complete.
 pub fn walk2(
     filter: filter::Filter,
     input: git2::Oid,
+    hide: Option<git2::Oid>,
     transaction: &cache::Transaction,
-) -> JoshResult<()> {
+) -> JoshResult<WalkResult> {
     rs_tracing::trace_scoped!("walk2","spec":filter::spec(filter), "id": input.to_string());
 
     ok_or!(transaction.repo().find_commit(input), {
-        return Ok(());
+        return Ok(WalkResult::Done);
     });
 
     if transaction.known(filter, input) {
-        return Ok(());
+        return Ok(WalkResult::Done);
     }
 
-    let (known, n_new) = find_known(filter, input, transaction)?;
+    let (known, n_new) = find_known(filter, input, hide, transaction)?;
 
     let walk = {
         let mut walk = transaction.repo().revwalk()?;
         walk.set_sorting(git2::Sort::REVERSE | git2::Sort::TOPOLOGICAL)?;
         walk.push(input)?;
-        for k in known.iter() {
+        for k in known.iter().chain(hide.iter()) {
             walk.hide(*k)?;
         }
         walk
@@ -38,6 +49,10 @@ pub fn walk2(
     let walks = transaction.new_walk();
 
     for original_commit_id in walk {
+        if transaction.cancelled() {
+            transaction.end_walk();
+            return Ok(WalkResult::Cancelled);
+        }
         if !filter::apply_to_commit3(
             filter,
             &transaction.repo().find_commit(original_commit_id?)?,
@@ -67,7 +82,7 @@ pub fn walk2(
 
     transaction.end_walk();
 
-    return Ok(());
+    return Ok(WalkResult::Done);
 }
 
 fn find_unapply_base(
@@ -137,15 +152,93 @@ pub fn find_original(
     return Ok(git2::Oid::zero());
 }
 
+/// Like `find_original`, but for several filtered commits at once, resolved
+/// in a single walk of the history. Entries of the result are `None` if no
+/// original was found for the filtered commit at the same index.
+pub fn find_original_batch(
+    transaction: &cache::Transaction,
+    filter: filter::Filter,
+    contained_in: git2::Oid,
+    filtered: &[git2::Oid],
+) -> super::JoshResult<Vec<Option<git2::Oid>>> {
+    let mut result = vec![None; filtered.len()];
+    if contained_in == git2::Oid::zero() || filtered.is_empty() {
+        return Ok(result);
+    }
+    let mut remaining = filtered.len();
+    let mut walk = transaction.repo().revwalk()?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+    walk.push(contained_in)?;
+
+    for original in walk {
+        let original = transaction.repo().find_commit(original?)?;
+        let f = filter::apply_to_commit(filter, &original, transaction)?;
+        let matching: Vec<usize> = (0..filtered.len())
+            .filter(|i| filtered[*i] == f && result[*i].is_none())
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+        if original.parent_ids().count() == 1 {
+            let fp =
+                filter::apply_to_commit(filter, &original.parents().next().unwrap(), transaction)?;
+
+            if fp == f {
+                continue;
+            }
+        }
+        for i in matching {
+            result[i] = Some(original.id());
+            remaining -= 1;
+        }
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Find all commits reachable from `contained_in` that map to `filtered`.
+/// Unlike `find_original` this always walks the whole history, because
+/// when filtering is many-to-one the originals need not be consecutive.
+pub fn find_originals(
+    transaction: &cache::Transaction,
+    filter: filter::Filter,
+    contained_in: git2::Oid,
+    filtered: git2::Oid,
+) -> super::JoshResult<Vec<git2::Oid>> {
+    let mut originals = vec![];
+    if contained_in == git2::Oid::zero() {
+        return Ok(originals);
+    }
+    let mut walk = transaction.repo().revwalk()?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+    walk.push(contained_in)?;
+
+    for original in walk {
+        let original = transaction.repo().find_commit(original?)?;
+        if filtered == filter::apply_to_commit(filter, &original, transaction)? {
+            originals.push(original.id());
+        }
+    }
+
+    return Ok(originals);
+}
+
 fn find_known(
     filter: filter::Filter,
     input: git2::Oid,
+    hide: Option<git2::Oid>,
     transaction: &cache::Transaction,
 ) -> JoshResult<(Vec<git2::Oid>, usize)> {
     log::debug!("find_known");
     let mut known = vec![];
     let mut walk = transaction.repo().revwalk()?;
     walk.push(input)?;
+    if let Some(hide) = hide {
+        walk.hide(hide)?;
+    }
 
     let n_new = walk
         .with_hide_callback(&|id| {
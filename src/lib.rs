@@ -37,6 +37,7 @@ extern crate serde_json;
 
 use tracing;
 
+pub mod archive;
 pub mod cache;
 pub mod filter;
 pub mod graphql;
@@ -277,3 +278,63 @@ pub fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
     }
     ret
 }
+
+lazy_static! {
+    static ref TRACE_FILE: std::sync::Mutex<bool> = std::sync::Mutex::new(false);
+}
+
+/// Start writing the events of `rs_tracing::trace_scoped!` and friends to
+/// `<pid>.trace` in `dir`. Does nothing if a trace file is already open.
+///
+/// This is not thread-safe. rs_tracing swaps its writer without synchronizing
+/// with threads that record events, and the lock in here only tracks whether a
+/// file is open. So call this and `close_trace_file` only during single-threaded
+/// setup and shutdown, like josh-filter does, never while other threads run.
+pub fn open_trace_file(dir: &std::path::Path) -> JoshResult<()> {
+    let mut open = TRACE_FILE.lock()?;
+    if !*open {
+        rs_tracing::open_trace_file!(dir)?;
+        *open = true;
+    }
+    Ok(())
+}
+
+/// Flush and close the trace file opened with `open_trace_file`.
+/// Not thread-safe either, see `open_trace_file`.
+pub fn close_trace_file() {
+    if let Ok(mut open) = TRACE_FILE.lock() {
+        if *open {
+            rs_tracing::close_trace_file!();
+            *open = false;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Create an empty bare repo for tests. All of them share one cache and
+    /// don't read the git config of the user running the tests.
+    pub fn test_repo(name: &str) -> git2::Repository {
+        lazy_static! {
+            static ref ROOT: std::path::PathBuf = {
+                let root = std::env::temp_dir().join(format!("josh-test-{}", std::process::id()));
+                std::fs::remove_dir_all(&root).ok();
+                std::fs::create_dir_all(&root).unwrap();
+                cache::load(&root).unwrap();
+                for level in [
+                    git2::ConfigLevel::System,
+                    git2::ConfigLevel::XDG,
+                    git2::ConfigLevel::Global,
+                ]
+                .iter()
+                {
+                    unsafe { git2::opts::set_search_path(*level, &root).unwrap() };
+                }
+                root
+            };
+        }
+        git2::Repository::init_bare(ROOT.join(name)).unwrap()
+    }
+}
@@ -0,0 +1,56 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init repo 1> /dev/null
+  $ cd repo
+
+  $ mkdir -p sub1 sub2/dir
+  $ echo contents1 > sub1/file1
+  $ echo contents2 > sub1/file2
+  $ echo contents3 > sub2/file1
+  $ echo contents4 > sub2/dir/file4
+  $ git add .
+  $ git commit -m "add files" 1> /dev/null
+
+  $ cat > warnings.graphql <<EOF
+  > query {
+  >  collision: rev(filter: ":[a=:/sub1,a=:/sub2]") {
+  >   warnings {
+  >    message
+  >   }
+  >  }
+  >  same_source: rev(filter: ":[::sub1/file1,::sub1/]") {
+  >   warnings {
+  >    message
+  >   }
+  >  }
+  >  combine: rev(filter: ":combine=sub1,sub2") {
+  >   warnings {
+  >    message
+  >   }
+  >  }
+  > }
+  > EOF
+  $ git add warnings.graphql
+  $ git commit -m "add query" 1> /dev/null
+
+  $ josh-filter -q "graphql=warnings.graphql"
+  {
+    "collision": {
+      "warnings": [
+        {
+          "message": "Path collision: \"sub1/file1\" and \"sub2/file1\" both map to \"a/file1\""
+        }
+      ]
+    },
+    "same_source": {
+      "warnings": []
+    },
+    "combine": {
+      "warnings": [
+        {
+          "message": "combine: \"file1\" exists in both \"sub1\" and \"sub2\""
+        }
+      ]
+    }
+  } (no-eol)
@@ -0,0 +1,54 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init real_repo 1> /dev/null
+  $ cd real_repo
+
+  $ mkdir -p libs/a libs/b libs/c/sub
+  $ echo contents1 > libs/a/file1
+  $ echo contents2 > libs/b/file2
+  $ echo contents3 > libs/c/sub/file3
+  $ echo contents4 > libs/c/file4
+  $ git add libs
+  $ git commit -m "add libs" 1> /dev/null
+
+  $ josh-filter -s :combine=libs/a,libs/b,libs/c master --update refs/heads/combined
+  [1] :combine=libs/a,libs/b,libs/c
+  $ git ls-tree -r --name-only combined
+  file1
+  file2
+  file4
+  sub/file3
+
+  $ git checkout combined 1> /dev/null
+  Switched to branch 'combined'
+  $ echo contents5 > sub/file5
+  $ echo contents6 > file6
+  $ echo changed > file2
+  $ git add .
+  $ git commit -m "change combined" 1> /dev/null
+
+  $ josh-filter -s :combine=libs/a,libs/b,libs/c --reverse master --update refs/heads/combined
+  [1] :combine=libs/a,libs/b,libs/c
+  $ git checkout master 1> /dev/null
+  Switched to branch 'master'
+  $ git ls-tree -r --name-only HEAD
+  libs/a/file1
+  libs/a/file6
+  libs/b/file2
+  libs/c/file4
+  libs/c/sub/file3
+  libs/c/sub/file5
+  $ cat libs/b/file2
+  changed
+
+  $ mkdir libs/d
+  $ echo contents7 > libs/d/file1
+  $ git add libs
+  $ git commit -m "add colliding file" 1> /dev/null
+
+  $ josh-filter :combine=libs/a,libs/d master --update refs/heads/colliding
+  WARNING: combine: "file1" exists in both "libs/a" and "libs/d"
+  $ git ls-tree -r --name-only colliding
+  fatal: Not a valid object name colliding
+  [128]
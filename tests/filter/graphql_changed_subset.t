@@ -0,0 +1,50 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init repo 1> /dev/null
+  $ cd repo
+
+  $ mkdir -p sub1/dir sub2
+  $ echo contents1 > sub1/file1
+  $ echo contents2 > sub1/file2
+  $ echo contents3 > sub1/dir/file3
+  $ git add .
+  $ git commit -m "add files" 1> /dev/null
+  $ echo changed > sub1/file2
+  $ echo contents4 > sub1/dir/file4
+  $ echo contents5 > sub2/file5
+  $ git rm -q sub1/file1
+  $ git add .
+  $ git commit -m "change files" 1> /dev/null
+
+  $ cat > sub2/x.graphql <<EOF
+  > query {
+  >  rev(filter: ":/sub1") {
+  >   changedSubset(since: "HEAD~2") {
+  >    filter
+  >    files {
+  >     path
+  >    }
+  >   }
+  >  }
+  > }
+  > EOF
+  $ git add .
+  $ git commit -m "add query" 1> /dev/null
+
+  $ josh-filter -q "graphql=sub2/x.graphql"
+  {
+    "rev": {
+      "changedSubset": {
+        "filter": ":/sub1:[::dir/file4,::file2]",
+        "files": [
+          {
+            "path": "dir/file4"
+          },
+          {
+            "path": "file2"
+          }
+        ]
+      }
+    }
+  } (no-eol)
@@ -0,0 +1,38 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init repo 1> /dev/null
+  $ cd repo
+
+  $ mkdir sub1 sub2
+  $ echo contents1 > sub1/file1
+  $ git add .
+  $ git commit -m "add file1" 1> /dev/null
+  $ git notes add -m "ci: passed"
+  $ git notes --ref review add -m "approved"
+  $ echo contents2 > sub2/file2
+  $ git add .
+  $ git commit -m "add file2" 1> /dev/null
+
+  $ cat > sub2/x.graphql <<EOF
+  > query {
+  >  rev(filter: ":/sub1") {
+  >   notes
+  >   review: notes(ref: "refs/notes/review")
+  >   missing: notes(ref: "refs/notes/missing")
+  >  }
+  >  notes
+  > }
+  > EOF
+  $ git add .
+  $ git commit -m "add query" 1> /dev/null
+
+  $ josh-filter -q "graphql=sub2/x.graphql"
+  {
+    "rev": {
+      "notes": "ci: passed\n",
+      "review": "approved\n",
+      "missing": null
+    },
+    "notes": null
+  } (no-eol)
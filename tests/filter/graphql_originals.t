@@ -0,0 +1,45 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init repo 1> /dev/null
+  $ cd repo
+
+  $ mkdir sub1 sub2
+  $ echo contents1 > sub1/file1
+  $ git add .
+  $ git commit -m "add file1" 1> /dev/null
+  $ echo contents2 > sub2/file2
+  $ git add .
+  $ git commit -m "add file2" 1> /dev/null
+  $ echo contents3 > sub2/file3
+  $ git add .
+  $ git commit -m "add file3" 1> /dev/null
+
+  $ cat > sub2/x.graphql <<EOF
+  > query {
+  >  rev(filter: ":/sub1") {
+  >   hash
+  >   originals
+  >  }
+  > }
+  > EOF
+  $ git add .
+  $ git commit -m "add query" 1> /dev/null
+
+  $ git log --pretty=%H
+  f856c983533cb4936932779ec3f89b3ccfc7ad23
+  7706671cc70a2ab88fd5591a29af2f8028b0355a
+  2f1810cd72f80911e056ada857718d2982cb954e
+  bb282e9cdc1b972fffd08fd21eead43bc0c83cb8
+  $ josh-filter -q "graphql=sub2/x.graphql"
+  {
+    "rev": {
+      "hash": "0b4cf6c9efbbda1eada39fa9c1d21d2525b027bb",
+      "originals": [
+        "f856c983533cb4936932779ec3f89b3ccfc7ad23",
+        "7706671cc70a2ab88fd5591a29af2f8028b0355a",
+        "2f1810cd72f80911e056ada857718d2982cb954e",
+        "bb282e9cdc1b972fffd08fd21eead43bc0c83cb8"
+      ]
+    }
+  } (no-eol)
@@ -0,0 +1,30 @@
+  $ export TESTTMP=${PWD}
+
+  $ cd ${TESTTMP}
+  $ git init libs 1> /dev/null
+  $ cd libs
+
+  $ mkdir sub1
+  $ echo "// @publish" > sub1/public
+  $ echo contents > sub1/private
+  $ git add sub1
+  $ git commit -m "add sub1" 1> /dev/null
+
+  $ echo contents > sub1/private2
+  $ git add sub1
+  $ git commit -m "add private2" 1> /dev/null
+
+  $ mkdir sub2
+  $ printf "@publish\0" > sub2/binary
+  $ echo "@publish" > sub2/public
+  $ git add sub2
+  $ git commit -m "add sub2" 1> /dev/null
+
+  $ josh-filter -s ':grep="@publish"'
+  [2] :grep="@publish"
+  $ git log --graph --pretty=%s FILTERED_HEAD
+  * add sub2
+  * add sub1
+  $ git ls-tree -r --name-only FILTERED_HEAD
+  sub1/public
+  sub2/public
@@ -66,82 +66,53 @@
         "subscriptionType": null,
         "types": [
           {
-            "description": null,
-            "enumValues": null,
-            "fields": null,
-            "inputFields": null,
-            "interfaces": null,
-            "kind": "SCALAR",
-            "name": "Boolean",
-            "possibleTypes": null
-          },
-          {
-            "description": null,
+            "description": "`kind` is \"blob\", \"tree\" or \"commit\" for submodules",
             "enumValues": null,
             "fields": [
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "commit",
-                    "type": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
-                        "ofType": null
-                      }
-                    }
-                  },
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "topic",
-                    "type": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
-                        "ofType": null
-                      }
-                    }
-                  },
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "add",
-                    "type": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "LIST",
-                        "name": null,
-                        "ofType": {
-                          "kind": "NON_NULL",
-                          "name": null,
-                          "ofType": {
-                            "kind": "INPUT_OBJECT",
-                            "name": "MarkersInput",
-                            "ofType": null
-                          }
-                        }
-                      }
-                    }
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "name",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
-                ],
+                }
+              },
+              {
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "meta",
+                "name": "oid",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "SCALAR",
-                    "name": "Boolean",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "kind",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
                     "ofType": null
                   }
                 }
@@ -150,11 +121,11 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "RepositoryMut",
+            "name": "EntryHash",
             "possibleTypes": null
           },
           {
-            "description": null,
+            "description": "`status` is one of \"added\", \"modified\", \"deleted\" and \"renamed\",\n`old_path` is only set for renamed paths",
             "enumValues": null,
             "fields": [
               {
@@ -162,7 +133,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "name",
+                "name": "status",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -178,25 +149,13 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "description",
-                "type": {
-                  "kind": "SCALAR",
-                  "name": "String",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "type",
+                "name": "path",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "OBJECT",
-                    "name": "__Type",
+                    "name": "Path",
                     "ofType": null
                   }
                 }
@@ -206,7 +165,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "defaultValue",
+                "name": "oldPath",
                 "type": {
                   "kind": "SCALAR",
                   "name": "String",
@@ -217,7 +176,7 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "__InputValue",
+            "name": "ChangedPath",
             "possibleTypes": null
           },
           {
@@ -229,76 +188,88 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "filter",
+                "name": "data",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "SCALAR",
-                    "name": "String",
-                    "ofType": null
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Document",
+                        "ofType": null
+                      }
+                    }
                   }
                 }
               },
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Number of markers on the path and below. Counts are cached until\n`refs/josh/meta` changes.",
                 "isDeprecated": false,
-                "name": "hash",
+                "name": "count",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "SCALAR",
-                    "name": "String",
+                    "name": "Int",
                     "ofType": null
                   }
                 }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "Markers",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "name",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
               },
               {
                 "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "summary",
+                "name": "description",
                 "type": {
-                  "kind": "NON_NULL",
-                  "name": null,
-                  "ofType": {
-                    "kind": "SCALAR",
-                    "name": "String",
-                    "ofType": null
-                  }
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "format",
-                    "type": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
-                        "ofType": null
-                      }
-                    }
-                  }
-                ],
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "date",
+                "name": "kind",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "SCALAR",
-                    "name": "String",
+                    "kind": "ENUM",
+                    "name": "__TypeKind",
                     "ofType": null
                   }
                 }
@@ -306,19 +277,9 @@
               {
                 "args": [
                   {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "filter",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "String",
-                      "ofType": null
-                    }
-                  },
-                  {
-                    "defaultValue": null,
+                    "defaultValue": "false",
                     "description": null,
-                    "name": "original",
+                    "name": "includeDeprecated",
                     "type": {
                       "kind": "SCALAR",
                       "name": "Boolean",
@@ -329,10 +290,30 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "rev",
+                "name": "fields",
+                "type": {
+                  "kind": "LIST",
+                  "name": null,
+                  "ofType": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "OBJECT",
+                      "name": "__Field",
+                      "ofType": null
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "ofType",
                 "type": {
                   "kind": "OBJECT",
-                  "name": "Revision",
+                  "name": "__Type",
                   "ofType": null
                 }
               },
@@ -341,52 +322,27 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "parents",
+                "name": "inputFields",
                 "type": {
-                  "kind": "NON_NULL",
+                  "kind": "LIST",
                   "name": null,
                   "ofType": {
-                    "kind": "LIST",
+                    "kind": "NON_NULL",
                     "name": null,
                     "ofType": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "OBJECT",
-                        "name": "Revision",
-                        "ofType": null
-                      }
+                      "kind": "OBJECT",
+                      "name": "__InputValue",
+                      "ofType": null
                     }
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "at",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "String",
-                      "ofType": null
-                    }
-                  },
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "depth",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "Int",
-                      "ofType": null
-                    }
-                  }
-                ],
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "files",
+                "name": "interfaces",
                 "type": {
                   "kind": "LIST",
                   "name": null,
@@ -395,39 +351,18 @@
                     "name": null,
                     "ofType": {
                       "kind": "OBJECT",
-                      "name": "Path",
+                      "name": "__Type",
                       "ofType": null
                     }
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "at",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "String",
-                      "ofType": null
-                    }
-                  },
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "depth",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "Int",
-                      "ofType": null
-                    }
-                  }
-                ],
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "dirs",
+                "name": "possibleTypes",
                 "type": {
                   "kind": "LIST",
                   "name": null,
@@ -436,7 +371,7 @@
                     "name": null,
                     "ofType": {
                       "kind": "OBJECT",
-                      "name": "Path",
+                      "name": "__Type",
                       "ofType": null
                     }
                   }
@@ -445,39 +380,12 @@
               {
                 "args": [
                   {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "path",
-                    "type": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
-                        "ofType": null
-                      }
-                    }
-                  }
-                ],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "file",
-                "type": {
-                  "kind": "OBJECT",
-                  "name": "Path",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [
-                  {
-                    "defaultValue": null,
+                    "defaultValue": "false",
                     "description": null,
-                    "name": "path",
+                    "name": "includeDeprecated",
                     "type": {
                       "kind": "SCALAR",
-                      "name": "String",
+                      "name": "Boolean",
                       "ofType": null
                     }
                   }
@@ -485,19 +393,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "dir",
-                "type": {
-                  "kind": "OBJECT",
-                  "name": "Path",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "warnings",
+                "name": "enumValues",
                 "type": {
                   "kind": "LIST",
                   "name": null,
@@ -506,7 +402,7 @@
                     "name": null,
                     "ofType": {
                       "kind": "OBJECT",
-                      "name": "Warning",
+                      "name": "__EnumValue",
                       "ofType": null
                     }
                   }
@@ -516,21 +412,11 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "Revision",
-            "possibleTypes": null
-          },
-          {
-            "description": null,
-            "enumValues": null,
-            "fields": null,
-            "inputFields": null,
-            "interfaces": null,
-            "kind": "SCALAR",
-            "name": "String",
+            "name": "__Type",
             "possibleTypes": null
           },
           {
-            "description": null,
+            "description": "`oldPath` is null for added files and `newPath` for deleted ones",
             "enumValues": null,
             "fields": [
               {
@@ -538,15 +424,11 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "name",
+                "name": "oldPath",
                 "type": {
-                  "kind": "NON_NULL",
-                  "name": null,
-                  "ofType": {
-                    "kind": "SCALAR",
-                    "name": "String",
-                    "ofType": null
-                  }
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
                 }
               },
               {
@@ -554,7 +436,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "description",
+                "name": "newPath",
                 "type": {
                   "kind": "SCALAR",
                   "name": "String",
@@ -566,22 +448,14 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "args",
+                "name": "insertions",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "LIST",
-                    "name": null,
-                    "ofType": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "OBJECT",
-                        "name": "__InputValue",
-                        "ofType": null
-                      }
-                    }
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
                   }
                 }
               },
@@ -590,13 +464,13 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "type",
+                "name": "deletions",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "OBJECT",
-                    "name": "__Type",
+                    "kind": "SCALAR",
+                    "name": "Int",
                     "ofType": null
                   }
                 }
@@ -604,36 +478,34 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Unified diff of the file",
                 "isDeprecated": false,
-                "name": "isDeprecated",
+                "name": "patch",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "SCALAR",
-                    "name": "Boolean",
+                    "name": "String",
                     "ofType": null
                   }
                 }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "deprecationReason",
-                "type": {
-                  "kind": "SCALAR",
-                  "name": "String",
-                  "ofType": null
-                }
               }
             ],
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "__Field",
+            "name": "FileDiff",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": null,
+            "inputFields": null,
+            "interfaces": null,
+            "kind": "SCALAR",
+            "name": "Float",
             "possibleTypes": null
           },
           {
@@ -645,7 +517,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "data",
+                "name": "warnings",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -657,7 +529,7 @@
                       "name": null,
                       "ofType": {
                         "kind": "OBJECT",
-                        "name": "Document",
+                        "name": "Warning",
                         "ofType": null
                       }
                     }
@@ -667,15 +539,15 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "`true` if not all checks were done in time, so there can be more warnings",
                 "isDeprecated": false,
-                "name": "count",
+                "name": "truncated",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "SCALAR",
-                    "name": "Int",
+                    "name": "Boolean",
                     "ofType": null
                   }
                 }
@@ -684,55 +556,2219 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "Markers",
+            "name": "Warnings",
             "possibleTypes": null
           },
           {
-            "description": "GraphQL type kind\n\nThe GraphQL specification defines a number of type kinds - the meta type of a type.",
-            "enumValues": [
-              {
-                "deprecationReason": null,
-                "description": "## Scalar types\n\nScalar types appear as the leaf nodes of GraphQL queries. Strings, numbers, and booleans are the built in types, and while it's possible to define your own, it's relatively uncommon.",
-                "isDeprecated": false,
-                "name": "SCALAR"
-              },
-              {
-                "deprecationReason": null,
-                "description": "## Object types\n\nThe most common type to be implemented by users. Objects have fields and can implement interfaces.",
-                "isDeprecated": false,
-                "name": "OBJECT"
-              },
+            "description": null,
+            "enumValues": null,
+            "fields": [
               {
+                "args": [],
                 "deprecationReason": null,
-                "description": "## Interface types\n\nInterface types are used to represent overlapping fields between multiple types, and can be queried for their concrete type.",
+                "description": null,
                 "isDeprecated": false,
-                "name": "INTERFACE"
+                "name": "types",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "__Type",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
               },
               {
+                "args": [],
                 "deprecationReason": null,
-                "description": "## Union types\n\nUnions are similar to interfaces but can not contain any fields on their own.",
+                "description": null,
                 "isDeprecated": false,
-                "name": "UNION"
+                "name": "queryType",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "__Type",
+                    "ofType": null
+                  }
+                }
               },
               {
+                "args": [],
                 "deprecationReason": null,
-                "description": "## Enum types\n\nLike scalars, enum types appear as the leaf nodes of GraphQL queries.",
+                "description": null,
                 "isDeprecated": false,
-                "name": "ENUM"
+                "name": "mutationType",
+                "type": {
+                  "kind": "OBJECT",
+                  "name": "__Type",
+                  "ofType": null
+                }
               },
               {
+                "args": [],
                 "deprecationReason": null,
-                "description": "## Input objects\n\nRepresents complex values provided in queries _into_ the system.",
+                "description": null,
                 "isDeprecated": false,
-                "name": "INPUT_OBJECT"
+                "name": "subscriptionType",
+                "type": {
+                  "kind": "OBJECT",
+                  "name": "__Type",
+                  "ofType": null
+                }
               },
               {
+                "args": [],
                 "deprecationReason": null,
-                "description": "## List types\n\nRepresent lists of other types. This library provides implementations for vectors and slices, but other Rust types can be extended to serve as GraphQL lists.",
+                "description": null,
                 "isDeprecated": false,
-                "name": "LIST"
-              },
-              {
+                "name": "directives",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "__Directive",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "__Schema",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": null,
+            "inputFields": null,
+            "interfaces": null,
+            "kind": "SCALAR",
+            "name": "Int",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "path",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "value",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "FlatEntry",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "name",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filter",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "rev",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Revision",
+                    "ofType": null
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "Reference",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": null,
+            "inputFields": null,
+            "interfaces": null,
+            "kind": "SCALAR",
+            "name": "Boolean",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "path",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "text",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "isBinary",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Why the file could not be read, null on success",
+                "isDeprecated": false,
+                "name": "error",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "FileContent",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "commit",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "topic",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "add",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "LIST",
+                        "name": null,
+                        "ofType": {
+                          "kind": "NON_NULL",
+                          "name": null,
+                          "ofType": {
+                            "kind": "INPUT_OBJECT",
+                            "name": "MarkersInput",
+                            "ofType": null
+                          }
+                        }
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "meta",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "commit",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "ref",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "text",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Attach `text` as git note to `commit` in the notes ref `ref`\n(default \"refs/notes/commits\"), replacing an existing note",
+                "isDeprecated": false,
+                "name": "setNote",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "branch",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Point `HEAD` of the repo at `branch`, given either as \"master\" or as\n\"refs/heads/master\". The branch has to exist.",
+                "isDeprecated": false,
+                "name": "setDefaultBranch",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filter",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "from",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "onto",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Upstream commits, oldest first, that pushing the filtered commit `from` through\n`filter` onto the upstream ref `onto` (default the branch `HEAD` points to) would\ncreate. This is a mutation because the commits are written to the object\ndatabase, but no refs are changed.",
+                "isDeprecated": false,
+                "name": "previewPush",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "RepositoryMut",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "name",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "description",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "type",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "__Type",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "defaultValue",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "__InputValue",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "filter",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Whether the filter of this revision changes the content, `false` for\n`:nop` and filters equivalent to it",
+                "isDeprecated": false,
+                "name": "filtered",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Opaque handle of the filter that can be passed to `Repository.rev`",
+                "isDeprecated": false,
+                "name": "filterId",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "hash",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Id of the filtered tree (or its subdirectory `at`), independent of commit metadata",
+                "isDeprecated": false,
+                "name": "treeHash",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "summary",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Full message of the filtered commit, including trailers",
+                "isDeprecated": false,
+                "name": "message",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Message of the filtered commit without the summary line and the blank lines after it",
+                "isDeprecated": false,
+                "name": "body",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "`key: value` lines of the trailer block that ends the filtered commit message",
+                "isDeprecated": false,
+                "name": "trailers",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Trailer",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Name of the author of the filtered commit, empty if it is not valid UTF-8",
+                "isDeprecated": false,
+                "name": "authorName",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Email of the author of the filtered commit, empty if it is not valid UTF-8",
+                "isDeprecated": false,
+                "name": "authorEmail",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Name of the committer of the filtered commit, empty if it is not valid UTF-8",
+                "isDeprecated": false,
+                "name": "committerName",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Email of the committer of the filtered commit, empty if it is not valid UTF-8",
+                "isDeprecated": false,
+                "name": "committerEmail",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "format",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "utc",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Commit time of the filtered commit formatted with the strftime style `format`,\nin the timezone of the commit, or in UTC with `utc: true`",
+                "isDeprecated": false,
+                "name": "date",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "from",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Time since the filtered commit in words, like \"3 days ago\". `from` is the\nRFC 3339 time to compare to instead of now.",
+                "isDeprecated": false,
+                "name": "age",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "time",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Whether the filtered commit was committed strictly after the RFC3339 `time`",
+                "isDeprecated": false,
+                "name": "committedAfter",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "time",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Whether the filtered commit was committed strictly before the RFC3339 `time`",
+                "isDeprecated": false,
+                "name": "committedBefore",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filter",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "original",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "rev",
+                "type": {
+                  "kind": "OBJECT",
+                  "name": "Revision",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Ids of all original commits that map to the same filtered commit as this one",
+                "isDeprecated": false,
+                "name": "originals",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Whether the filtered tree differs from the one of the first parent, or is\nnot empty for a commit without parents. Only trees are filtered, not commits.",
+                "isDeprecated": false,
+                "name": "touchesFilter",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "since",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Same as this revision, but limited to the files changed since `since`\nin the filtered history",
+                "isDeprecated": false,
+                "name": "changedSubset",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Revision",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "against",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Changes of the filtered commit compared to its first parent, or to the filtered\ntree of `against` if given. Commits without parents are compared to an empty tree.",
+                "isDeprecated": false,
+                "name": "diff",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "FileDiff",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "against",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Paths that differ between the filtered trees of `against` and this revision.\nWithout `against` the first parent of the original commit is used, or an empty\ntree for a root commit. Deleted paths refer to the tree of `against`.",
+                "isDeprecated": false,
+                "name": "changedPaths",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "ChangedPath",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "other",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Whether this commit is `other` or one of its ancestors. This compares the\noriginal commits, so the result does not depend on the filter.",
+                "isDeprecated": false,
+                "name": "reachableFrom",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "since",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Summary of the commits in the filtered history between `since` and this\nrevision, like `git log --numstat since..` would show it. Merge commits are\ncounted but their changes are not, as they are part of the merged commits.",
+                "isDeprecated": false,
+                "name": "rangeStats",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "RangeStats",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "ref",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Text of the git note in the notes ref `ref` (default \"refs/notes/commits\")\nattached to the original commit this filtered commit was created from",
+                "isDeprecated": false,
+                "name": "notes",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Number of parents of the filtered commit. Filtering can drop parents\nthat don't contribute to the filtered history, so this can be less\nthan the number of parents of the original commit.",
+                "isDeprecated": false,
+                "name": "parentCount",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Whether the filtered commit has more than one parent",
+                "isDeprecated": false,
+                "name": "isMerge",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "limit",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Parents of the filtered commit, at most `limit` of them if given. It is an\nerror if no original commit can be found for one of the returned parents.",
+                "isDeprecated": false,
+                "name": "parents",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Revision",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "pattern",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "limit",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Commits of the filtered history, newest first, whose filtered message matches\nthe regex `pattern`, at most `limit` of them if given. This only queries the\nhistory, the filter is not changed. Like for `parents` it is an error if no\noriginal commit can be found for one of them.",
+                "isDeprecated": false,
+                "name": "historyMatching",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Revision",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "limit",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Number of commits reachable from the filtered commit, so commits of the filtered\nhistory, not of the original one. With `limit` counting stops there.",
+                "isDeprecated": false,
+                "name": "commitCount",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "recursive",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Number of entries of the directory `at` in the filtered tree, of any kind.\nWith `recursive` the entries of all subdirectories are included.",
+                "isDeprecated": false,
+                "name": "entryCount",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "depth",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "sort",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "strict",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "glob",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Files below `at`. If `at` is not a directory this is an error,\nunless `strict` is set to `false`, then the result is empty.\nWith `glob` only paths matching it relative to `at` are returned.",
+                "isDeprecated": false,
+                "name": "files",
+                "type": {
+                  "kind": "LIST",
+                  "name": null,
+                  "ofType": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "OBJECT",
+                      "name": "Path",
+                      "ofType": null
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "depth",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "strict",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "glob",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "first",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "after",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Page of the sorted files, the other arguments work like for `files`.\nAt most `first` paths following the path `after` are returned, pass the\nlast path of a page as `after` to get the next one.",
+                "isDeprecated": false,
+                "name": "filesPage",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "PathPage",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "depth",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "sort",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "strict",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "glob",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Directories below `at`, `strict` and `glob` work like for `files`",
+                "isDeprecated": false,
+                "name": "dirs",
+                "type": {
+                  "kind": "LIST",
+                  "name": null,
+                  "ofType": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "OBJECT",
+                      "name": "Path",
+                      "ofType": null
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "depth",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "strict",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "glob",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "first",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "after",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Page of the sorted directories, the arguments work like for `filesPage`",
+                "isDeprecated": false,
+                "name": "dirsPage",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "PathPage",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "pattern",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Sorted paths of all files and directories in the filtered tree matching `pattern`",
+                "isDeprecated": false,
+                "name": "glob",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "paths",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "LIST",
+                        "name": null,
+                        "ofType": {
+                          "kind": "NON_NULL",
+                          "name": null,
+                          "ofType": {
+                            "kind": "SCALAR",
+                            "name": "String",
+                            "ofType": null
+                          }
+                        }
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Contents of several files, filtering the tree only once. Paths that\ncan't be read have `error` set instead of failing the whole request.",
+                "isDeprecated": false,
+                "name": "texts",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "FileContent",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "format",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Base64 encoded archive of the filtered tree (or the subdirectory\ngiven by `at`). Supported formats are \"tar\" and \"zip\".",
+                "isDeprecated": false,
+                "name": "archive",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "path",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "file",
+                "type": {
+                  "kind": "OBJECT",
+                  "name": "Path",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "a",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "b",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Whether the files `a` and `b` of the filtered tree have the same content",
+                "isDeprecated": false,
+                "name": "sameContent",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "kind",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Package manifest in the root of the filtered tree: `Cargo.toml` for `kind: \"cargo\"`,\n`package.json` for `\"npm\"` and `pyproject.toml` for `\"python\"`",
+                "isDeprecated": false,
+                "name": "manifest",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Document",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Root of the filtered tree, the same as `dir` without a path",
+                "isDeprecated": false,
+                "name": "tree",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Path",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "path",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "dir",
+                "type": {
+                  "kind": "OBJECT",
+                  "name": "Path",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "warnings",
+                "type": {
+                  "kind": "LIST",
+                  "name": null,
+                  "ofType": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "OBJECT",
+                      "name": "Warning",
+                      "ofType": null
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "timeoutMs",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Same as `warnings`, but the checks stop after `timeoutMs` milliseconds\n(default 1000) to keep the query responsive on large trees",
+                "isDeprecated": false,
+                "name": "boundedWarnings",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Warnings",
+                    "ofType": null
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "Revision",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": null,
+            "inputFields": null,
+            "interfaces": null,
+            "kind": "SCALAR",
+            "name": "String",
+            "possibleTypes": null
+          },
+          {
+            "description": "`hasMore` is true if there are more paths after the last one of `items`",
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "items",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Path",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "hasMore",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "PathPage",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "name",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "description",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "args",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "__InputValue",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "type",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "__Type",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "isDeprecated",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Boolean",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "deprecationReason",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "__Field",
+            "possibleTypes": null
+          },
+          {
+            "description": "GraphQL type kind\n\nThe GraphQL specification defines a number of type kinds - the meta type of a type.",
+            "enumValues": [
+              {
+                "deprecationReason": null,
+                "description": "## Scalar types\n\nScalar types appear as the leaf nodes of GraphQL queries. Strings, numbers, and booleans are the built in types, and while it's possible to define your own, it's relatively uncommon.",
+                "isDeprecated": false,
+                "name": "SCALAR"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## Object types\n\nThe most common type to be implemented by users. Objects have fields and can implement interfaces.",
+                "isDeprecated": false,
+                "name": "OBJECT"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## Interface types\n\nInterface types are used to represent overlapping fields between multiple types, and can be queried for their concrete type.",
+                "isDeprecated": false,
+                "name": "INTERFACE"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## Union types\n\nUnions are similar to interfaces but can not contain any fields on their own.",
+                "isDeprecated": false,
+                "name": "UNION"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## Enum types\n\nLike scalars, enum types appear as the leaf nodes of GraphQL queries.",
+                "isDeprecated": false,
+                "name": "ENUM"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## Input objects\n\nRepresents complex values provided in queries _into_ the system.",
+                "isDeprecated": false,
+                "name": "INPUT_OBJECT"
+              },
+              {
+                "deprecationReason": null,
+                "description": "## List types\n\nRepresent lists of other types. This library provides implementations for vectors and slices, but other Rust types can be extended to serve as GraphQL lists.",
+                "isDeprecated": false,
+                "name": "LIST"
+              },
+              {
                 "deprecationReason": null,
                 "description": "## Non-null types\n\nIn GraphQL, nullable types are the default. By putting a `!` after a type, it becomes non-nullable.",
                 "isDeprecated": false,
@@ -753,9 +2789,233 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "path",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "relative",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "dir",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Path",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "topic",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "meta",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Markers",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filter",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "rev",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Revision",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "The most recent commit that changed this path, following first parents.\nEvery step of the walk filters one tree, so the walk is limited to\n`LAST_MODIFIED_LIMIT` commits, beyond that an error is returned.",
+                "isDeprecated": false,
+                "name": "lastModified",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Revision",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Git attributes that apply to this file in the unfiltered tree, so they\ncan be written to a `.gitattributes` file when exporting the filtered tree",
+                "isDeprecated": false,
+                "name": "attributes",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "specs",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "LIST",
+                        "name": null,
+                        "ofType": {
+                          "kind": "NON_NULL",
+                          "name": null,
+                          "ofType": {
+                            "kind": "SCALAR",
+                            "name": "String",
+                            "ofType": null
+                          }
+                        }
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "For each filter in `specs` whether this file is part of its output\nwhen applied to the original tree of the commit",
+                "isDeprecated": false,
+                "name": "survivesFilters",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "FilterSurvival",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "hash",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Content of the file, `null` if it is not valid UTF-8",
+                "isDeprecated": false,
+                "name": "text",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Content of the file with invalid UTF-8 sequences replaced by `U+FFFD`",
                 "isDeprecated": false,
-                "name": "path",
+                "name": "textLossy",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -767,94 +3027,137 @@
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "relative",
-                    "type": {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Git file mode of the entry in octal, like `100644`, `100755`, `120000` for\nsymlinks, `160000` for submodules and `040000` for directories",
+                "isDeprecated": false,
+                "name": "mode",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Names and object ids of the immediate children of a directory, sorted by name",
+                "isDeprecated": false,
+                "name": "entryHashes",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
                       "kind": "NON_NULL",
                       "name": null,
                       "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
+                        "kind": "OBJECT",
+                        "name": "EntryHash",
                         "ofType": null
                       }
                     }
                   }
-                ],
+                }
+              },
+              {
+                "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Size of the file in bytes, an error for directories",
                 "isDeprecated": false,
-                "name": "dir",
+                "name": "size",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "OBJECT",
-                    "name": "Path",
+                    "kind": "SCALAR",
+                    "name": "Float",
                     "ofType": null
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "topic",
-                    "type": {
+                "args": [],
+                "deprecationReason": null,
+                "description": "For every line of the file the filtered commit that last changed it. This blames\nthe filtered history, so the hashes are the ones `Revision.hash` returns.",
+                "isDeprecated": false,
+                "name": "blame",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
                       "kind": "NON_NULL",
                       "name": null,
                       "ofType": {
-                        "kind": "SCALAR",
-                        "name": "String",
+                        "kind": "OBJECT",
+                        "name": "BlameLine",
                         "ofType": null
                       }
                     }
                   }
-                ],
+                }
+              },
+              {
+                "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Content of the file encoded as base64, for binary files",
                 "isDeprecated": false,
-                "name": "meta",
+                "name": "base64",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "OBJECT",
-                    "name": "Markers",
+                    "kind": "SCALAR",
+                    "name": "String",
                     "ofType": null
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": null,
-                    "description": null,
-                    "name": "filter",
-                    "type": {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Content of the file as a list of byte values, for files of at most\n`Context::set_max_raw_bytes_size` bytes",
+                "isDeprecated": false,
+                "name": "rawBytes",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
                       "kind": "NON_NULL",
                       "name": null,
                       "ofType": {
                         "kind": "SCALAR",
-                        "name": "String",
+                        "name": "Int",
                         "ofType": null
                       }
                     }
                   }
-                ],
+                }
+              },
+              {
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "rev",
+                "name": "toml",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
                     "kind": "OBJECT",
-                    "name": "Revision",
+                    "name": "Document",
                     "ofType": null
                   }
                 }
@@ -864,13 +3167,13 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "hash",
+                "name": "json",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "SCALAR",
-                    "name": "String",
+                    "kind": "OBJECT",
+                    "name": "Document",
                     "ofType": null
                   }
                 }
@@ -880,19 +3183,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "text",
-                "type": {
-                  "kind": "SCALAR",
-                  "name": "String",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "toml",
+                "name": "yaml",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -906,9 +3197,9 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "XML converted to JSON: attributes become `@name` keys, child elements keys by\nname, lists if a name repeats, and the text `#text`. Elements with only text\nbecome strings. The root element is the only key of the result.",
                 "isDeprecated": false,
-                "name": "json",
+                "name": "xml",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -922,9 +3213,9 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Rows of a CSV file as a list of objects keyed by the header row.\nAll values are strings, a file without rows gives an empty list.",
                 "isDeprecated": false,
-                "name": "yaml",
+                "name": "csv",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -943,7 +3234,7 @@
             "possibleTypes": null
           },
           {
-            "description": null,
+            "description": "`line` is 1 based, `commit` is the id of a filtered commit",
             "enumValues": null,
             "fields": [
               {
@@ -951,69 +3242,30 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "name",
-                "type": {
-                  "kind": "SCALAR",
-                  "name": "String",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "description",
-                "type": {
-                  "kind": "SCALAR",
-                  "name": "String",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "kind",
+                "name": "line",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "ENUM",
-                    "name": "__TypeKind",
+                    "kind": "SCALAR",
+                    "name": "Int",
                     "ofType": null
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": "false",
-                    "description": null,
-                    "name": "includeDeprecated",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "Boolean",
-                      "ofType": null
-                    }
-                  }
-                ],
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "fields",
+                "name": "commit",
                 "type": {
-                  "kind": "LIST",
+                  "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "NON_NULL",
-                    "name": null,
-                    "ofType": {
-                      "kind": "OBJECT",
-                      "name": "__Field",
-                      "ofType": null
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
               },
@@ -1022,30 +3274,14 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "ofType",
-                "type": {
-                  "kind": "OBJECT",
-                  "name": "__Type",
-                  "ofType": null
-                }
-              },
-              {
-                "args": [],
-                "deprecationReason": null,
-                "description": null,
-                "isDeprecated": false,
-                "name": "inputFields",
+                "name": "authorName",
                 "type": {
-                  "kind": "LIST",
+                  "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "NON_NULL",
-                    "name": null,
-                    "ofType": {
-                      "kind": "OBJECT",
-                      "name": "__InputValue",
-                      "ofType": null
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
               },
@@ -1054,69 +3290,57 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "interfaces",
+                "name": "authorEmail",
                 "type": {
-                  "kind": "LIST",
+                  "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "NON_NULL",
-                    "name": null,
-                    "ofType": {
-                      "kind": "OBJECT",
-                      "name": "__Type",
-                      "ofType": null
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
-              },
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "BlameLine",
+            "possibleTypes": null
+          },
+          {
+            "description": "A `key: value` line of the trailer block of a commit message, indented\ncontinuation lines are joined to the value",
+            "enumValues": null,
+            "fields": [
               {
                 "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "possibleTypes",
+                "name": "key",
                 "type": {
-                  "kind": "LIST",
+                  "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "NON_NULL",
-                    "name": null,
-                    "ofType": {
-                      "kind": "OBJECT",
-                      "name": "__Type",
-                      "ofType": null
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
               },
               {
-                "args": [
-                  {
-                    "defaultValue": "false",
-                    "description": null,
-                    "name": "includeDeprecated",
-                    "type": {
-                      "kind": "SCALAR",
-                      "name": "Boolean",
-                      "ofType": null
-                    }
-                  }
-                ],
+                "args": [],
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "enumValues",
+                "name": "value",
                 "type": {
-                  "kind": "LIST",
+                  "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "NON_NULL",
-                    "name": null,
-                    "ofType": {
-                      "kind": "OBJECT",
-                      "name": "__EnumValue",
-                      "ofType": null
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
               }
@@ -1124,7 +3348,7 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "__Type",
+            "name": "Trailer",
             "possibleTypes": null
           },
           {
@@ -1295,7 +3519,7 @@
                   }
                 ],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Integers clamped to the 32 bit range of GraphQL `Int`, see `bigint` for larger numbers",
                 "isDeprecated": false,
                 "name": "int",
                 "type": {
@@ -1304,6 +3528,142 @@
                   "ofType": null
                 }
               },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "default",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Integers of the full 64 bit range, as decimal string",
+                "isDeprecated": false,
+                "name": "bigint",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "default",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Float",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Numbers as float, integers included",
+                "isDeprecated": false,
+                "name": "float",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "Float",
+                  "ofType": null
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Sorted keys of an object, `null` for other values and, like for `value`,\nif nothing exists at the pointer `at`",
+                "isDeprecated": false,
+                "name": "keys",
+                "type": {
+                  "kind": "LIST",
+                  "name": null,
+                  "ofType": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "program",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Outputs of the jq `program` run on this document",
+                "isDeprecated": false,
+                "name": "jq",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Document",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
               {
                 "args": [
                   {
@@ -1377,6 +3737,61 @@
                     "ofType": null
                   }
                 }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "All scalar leaves of the document with their JSON Pointer",
+                "isDeprecated": false,
+                "name": "flatten",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "FlatEntry",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "ops",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Apply a JSON Patch (RFC 6902) and return the resulting document",
+                "isDeprecated": false,
+                "name": "patch",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "OBJECT",
+                    "name": "Document",
+                    "ofType": null
+                  }
+                }
               }
             ],
             "inputFields": null,
@@ -1394,22 +3809,14 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "types",
+                "name": "spec",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "LIST",
-                    "name": null,
-                    "ofType": {
-                      "kind": "NON_NULL",
-                      "name": null,
-                      "ofType": {
-                        "kind": "OBJECT",
-                        "name": "__Type",
-                        "ofType": null
-                      }
-                    }
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
                   }
                 }
               },
@@ -1418,13 +3825,13 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "queryType",
+                "name": "survives",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "OBJECT",
-                    "name": "__Type",
+                    "kind": "SCALAR",
+                    "name": "Boolean",
                     "ofType": null
                   }
                 }
@@ -1432,25 +3839,40 @@
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Path of the file in the output of the filter, if it survives",
                 "isDeprecated": false,
-                "name": "mutationType",
+                "name": "path",
                 "type": {
-                  "kind": "OBJECT",
-                  "name": "__Type",
+                  "kind": "SCALAR",
+                  "name": "String",
                   "ofType": null
                 }
-              },
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "FilterSurvival",
+            "possibleTypes": null
+          },
+          {
+            "description": null,
+            "enumValues": null,
+            "fields": [
               {
                 "args": [],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Name of the branch, like \"refs/heads/master\"",
                 "isDeprecated": false,
-                "name": "subscriptionType",
+                "name": "branch",
                 "type": {
-                  "kind": "OBJECT",
-                  "name": "__Type",
-                  "ofType": null
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
                 }
               },
               {
@@ -1458,7 +3880,7 @@
                 "deprecationReason": null,
                 "description": null,
                 "isDeprecated": false,
-                "name": "directives",
+                "name": "warnings",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -1470,28 +3892,18 @@
                       "name": null,
                       "ofType": {
                         "kind": "OBJECT",
-                        "name": "__Directive",
+                        "name": "Warning",
                         "ofType": null
                       }
                     }
                   }
                 }
               }
-            ],
-            "inputFields": null,
-            "interfaces": [],
-            "kind": "OBJECT",
-            "name": "__Schema",
-            "possibleTypes": null
-          },
-          {
-            "description": null,
-            "enumValues": null,
-            "fields": null,
+            ],
             "inputFields": null,
-            "interfaces": null,
-            "kind": "SCALAR",
-            "name": "Int",
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "BranchWarnings",
             "possibleTypes": null
           },
           {
@@ -1614,6 +4026,81 @@
             "name": "__DirectiveLocation",
             "possibleTypes": null
           },
+          {
+            "description": "`filesChanged` counts every path at most once, even if several commits changed it",
+            "enumValues": null,
+            "fields": [
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "commits",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "filesChanged",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "insertions",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": null,
+                "isDeprecated": false,
+                "name": "deletions",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "Int",
+                    "ofType": null
+                  }
+                }
+              }
+            ],
+            "inputFields": null,
+            "interfaces": [],
+            "kind": "OBJECT",
+            "name": "RangeStats",
+            "possibleTypes": null
+          },
           {
             "description": null,
             "enumValues": null,
@@ -1769,6 +4256,34 @@
                   }
                 }
               },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Encoded name as used in ref names, for example in\n`refs/josh/upstream/<namespace>.git/refs/heads/master`",
+                "isDeprecated": false,
+                "name": "namespace",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  }
+                }
+              },
+              {
+                "args": [],
+                "deprecationReason": null,
+                "description": "Branch `HEAD` points to, like \"refs/heads/master\", if it is set",
+                "isDeprecated": false,
+                "name": "defaultBranch",
+                "type": {
+                  "kind": "SCALAR",
+                  "name": "String",
+                  "ofType": null
+                }
+              },
               {
                 "args": [
                   {
@@ -1780,10 +4295,20 @@
                       "name": "String",
                       "ofType": null
                     }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "includeStale",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
                   }
                 ],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Refs deleted upstream are pruned on fetch. With `includeStale: false`\nrefs that are left pointing to a commit that does not exist are omitted as well.",
                 "isDeprecated": false,
                 "name": "refs",
                 "type": {
@@ -1804,6 +4329,80 @@
                   }
                 }
               },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "under",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Distinct next path segments of the branch names below `under`, sorted.\nWith branches \"feature/a\" and \"release/1\", `under: \"\"` returns\n`[\"feature\", \"release\"]` and `under: \"feature\"` returns `[\"a\"]`.",
+                "isDeprecated": false,
+                "name": "refPrefixes",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
+              {
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filter",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  }
+                ],
+                "deprecationReason": null,
+                "description": "Warnings of `filter` for the tip of every branch, like `Revision.warnings`",
+                "isDeprecated": false,
+                "name": "warnings",
+                "type": {
+                  "kind": "NON_NULL",
+                  "name": null,
+                  "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "BranchWarnings",
+                        "ofType": null
+                      }
+                    }
+                  }
+                }
+              },
               {
                 "args": [
                   {
@@ -1829,10 +4428,20 @@
                       "name": "String",
                       "ofType": null
                     }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "filterId",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
                   }
                 ],
                 "deprecationReason": null,
-                "description": null,
+                "description": "`filterId` can be used instead of `filter` to pass the id of a filter\npreviously returned by `Revision.filterId`, avoiding to parse the spec again.",
                 "isDeprecated": false,
                 "name": "rev",
                 "type": {
@@ -1844,24 +4453,38 @@
                     "ofType": null
                   }
                 }
-              }
-            ],
-            "inputFields": null,
-            "interfaces": [],
-            "kind": "OBJECT",
-            "name": "Repository",
-            "possibleTypes": null
-          },
-          {
-            "description": null,
-            "enumValues": null,
-            "fields": [
+              },
               {
-                "args": [],
+                "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "name",
+                    "type": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "SCALAR",
+                        "name": "String",
+                        "ofType": null
+                      }
+                    }
+                  },
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "at",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "String",
+                      "ofType": null
+                    }
+                  }
+                ],
                 "deprecationReason": null,
-                "description": null,
+                "description": "Spec of the filter defined as `name` in the `.josh` file at the root of the\ntree of `at` (default the branch `HEAD` points to). The file has the format\nof a workspace file, with `name = :filter` entries:\n\n```text\n# Views of this repo\ndocs = :/docs\napi = :[\n    ::src/api/\n    ::proto/\n]\n```",
                 "isDeprecated": false,
-                "name": "name",
+                "name": "namedFilter",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
@@ -1874,6 +4497,16 @@
               },
               {
                 "args": [
+                  {
+                    "defaultValue": null,
+                    "description": null,
+                    "name": "limit",
+                    "type": {
+                      "kind": "SCALAR",
+                      "name": "Int",
+                      "ofType": null
+                    }
+                  },
                   {
                     "defaultValue": null,
                     "description": null,
@@ -1886,16 +4519,24 @@
                   }
                 ],
                 "deprecationReason": null,
-                "description": null,
+                "description": "The `limit` (default 10) most recent commits on any branch, newest first.\nWith `filter` commits that don't change the filtered content are skipped.",
                 "isDeprecated": false,
-                "name": "rev",
+                "name": "recentCommits",
                 "type": {
                   "kind": "NON_NULL",
                   "name": null,
                   "ofType": {
-                    "kind": "OBJECT",
-                    "name": "Revision",
-                    "ofType": null
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                      "kind": "NON_NULL",
+                      "name": null,
+                      "ofType": {
+                        "kind": "OBJECT",
+                        "name": "Revision",
+                        "ofType": null
+                      }
+                    }
                   }
                 }
               }
@@ -1903,7 +4544,7 @@
             "inputFields": null,
             "interfaces": [],
             "kind": "OBJECT",
-            "name": "Reference",
+            "name": "Repository",
             "possibleTypes": null
           }
         ]